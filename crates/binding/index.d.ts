@@ -3,14 +3,34 @@
 export declare class JsSpecifications {
   static create(cwd: string, opts?: RawSpecificationsRenderOpts | undefined | null): JsSpecifications
   fixLint(): Promise<Array<JsMessages>>
+  fixLintDryRun(): Promise<JsPatch>
+  /** Snapshot the current problems into `.sfconfig/baseline.json`, returns the number of entries */
+  updateBaseline(): Promise<number>
+  /** Every available check with the codes it can emit */
+  listValidators(): Array<JsValidatorMeta>
+  /** spec.json with its `extends` resolved and the effective policy, as JSON */
+  printConfig(): string
+  validateSfconfig(): Promise<Array<JsMessages>>
   validateNpmrc(): Promise<Array<JsMessages>>
   validateNodeVersion(): Promise<Array<JsMessages>>
   validatePackageJson(): Promise<Array<JsMessages>>
   validateLint(): Promise<Array<JsMessages>>
   validateSyntax(): Promise<Array<JsMessages>>
+  /**
+   * Validate everything, then again whenever files under `cwd` change
+   *
+   * `callback` first gets every file with diagnostics, then only the files whose messages changed.
+   */
+  watch(callback: ((err: Error | null, arg: JsWatchDelta) => any)): JsWatcher
   validateAll(): Promise<Array<JsMessages>>
 }
 
+/** A running `JsSpecifications.watch`, keeps the process alive until closed */
+export declare class JsWatcher {
+  /** Stop watching, waits for a running validation to finish */
+  close(): void
+}
+
 export declare function cloc(paths: Array<string>, opts?: RawClocOpts | undefined | null): Array<JsLanguageStats>
 
 export interface Diagnostic {
@@ -29,17 +49,60 @@ export interface GlobJsArgs {
   verbose?: boolean
   absolute?: boolean
   globals?: Record<string, string>
+  /** How the columns of `Position` are counted, defaults to `Char` */
+  columnEncoding?: JsColumnEncoding
+  /** Reuse lint and syntax results of unchanged files, defaults to `true` */
+  cache?: boolean
+  /** Only check the files `git` reports as changed, config files included only when changed */
+  changed?: JsChangedFiles
+  /** Overrides the `policy` of spec.json field by field */
+  policy?: JsPolicy
 }
 
 export declare function initializeLogger(level?: LogLevel | undefined | null): void
 
+/** Which files `git` reports as changed, the union of every option set */
+export interface JsChangedFiles {
+  /** Files changed since this ref branched off, uncommitted changes included, e.g. `origin/main` */
+  since?: string
+  /** Files in the index */
+  staged?: boolean
+  /** Files git does not track yet */
+  untracked?: boolean
+}
+
+export declare const enum JsColumnEncoding {
+  Byte = 'Byte',
+  Char = 'Char',
+  Utf16 = 'Utf16'
+}
+
+export interface JsDiagnosticCode {
+  namespace: string
+  validator?: string
+  rule: string
+}
+
 export interface JsDiagnostics {
   message: string
   code?: string
+  codeInfo?: JsDiagnosticCode
   severity?: JsSeverity
   help?: string
   url?: string
   labels?: Array<JsLabeledSpan>
+  fix?: JsFix
+}
+
+export interface JsFilePatch {
+  path: string
+  isNew: boolean
+  diff: string
+}
+
+export interface JsFix {
+  message?: string
+  edits: Array<JsTextEdit>
 }
 
 export interface JsLabeledSpan {
@@ -287,15 +350,54 @@ export declare const enum JsLanguageType {
 
 export interface JsMessages {
   sourcePath: string
+  validator?: string
+  /** Name of the workspace package the file belongs to, `None` at the root or outside a monorepo */
+  package?: string
   diagnostics: Array<JsDiagnostics>
 }
 
+export interface JsMessagesDelta {
+  validator: string
+  sourcePath: string
+  /** Replaces what was reported before for `validator` and `sourcePath`, empty when the file is clean now or gone */
+  messages: Array<JsMessages>
+}
+
 export declare const enum JsSeverity {
   Error = 'Error',
   Warning = 'Warning',
   Advice = 'Advice'
 }
 
+export interface JsPackageJsonPolicy {
+  name?: boolean
+  private?: JsPrivatePolicy
+  packageManager?: boolean
+  shineoutVersion?: boolean
+}
+
+export interface JsPatch {
+  files: Array<JsFilePatch>
+  /** All file diffs concatenated, can be applied with `git apply` */
+  patch: string
+}
+
+/** Overrides the `policy` of spec.json field by field */
+export interface JsPolicy {
+  /** Ranges `.node-version` must satisfy one of, empty skips the range check */
+  nodeVersions?: Array<string>
+  /** Registries `.npmrc` may point at, empty skips the registry check */
+  registries?: Array<string>
+  packageJson?: JsPackageJsonPolicy
+}
+
+export declare const enum JsPrivatePolicy {
+  Off = 'Off',
+  Exist = 'Exist',
+  True = 'True',
+  False = 'False'
+}
+
 export interface JsSourceLocation {
   start: JsSourcePosition
   end: JsSourcePosition
@@ -311,6 +413,29 @@ export interface JsSourceSpan {
   length: number
 }
 
+export interface JsTextEdit {
+  /** Byte offset into the UTF-8 source, use `loc` for positions in JavaScript strings */
+  start: number
+  /** Byte offset into the UTF-8 source, exclusive */
+  end: number
+  loc: JsSourceLocation
+  content: string
+}
+
+export interface JsValidatorMeta {
+  id: string
+  description: string
+  /** The codes the validator can emit */
+  codes: Array<string>
+  fixable: boolean
+}
+
+export interface JsWatchDelta {
+  /** The validators that ran in this round */
+  validators: Array<string>
+  changes: Array<JsMessagesDelta>
+}
+
 export interface LabeledLoc {
   span: Span
   loc: Location
@@ -346,6 +471,8 @@ export interface RawSpecificationsRenderOpts {
   withDashboard?: boolean
   maxRenderCount?: number
   quiet?: boolean
+  /** How the columns of `JsSourcePosition` are counted, defaults to `Char` */
+  columnEncoding?: JsColumnEncoding
 }
 
 export interface Response {
@@ -353,6 +480,9 @@ export interface Response {
   map: Record<string, number>
 }
 
+/** The JSON Schema of `.sfconfig/spec.json`, for editors to complete and check the file */
+export declare function sfconfigSchema(): string
+
 export interface Span {
   offset: number
   length: number
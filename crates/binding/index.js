@@ -558,11 +558,15 @@ if (!nativeBinding) {
 
 module.exports = nativeBinding
 module.exports.JsSpecifications = nativeBinding.JsSpecifications
+module.exports.JsWatcher = nativeBinding.JsWatcher
 module.exports.cloc = nativeBinding.cloc
 module.exports.initializeLogger = nativeBinding.initializeLogger
+module.exports.JsColumnEncoding = nativeBinding.JsColumnEncoding
 module.exports.JsLanguageType = nativeBinding.JsLanguageType
+module.exports.JsPrivatePolicy = nativeBinding.JsPrivatePolicy
 module.exports.JsSeverity = nativeBinding.JsSeverity
 module.exports.LogLevel = nativeBinding.LogLevel
 module.exports.NaPiCategory = nativeBinding.NaPiCategory
+module.exports.sfconfigSchema = nativeBinding.sfconfigSchema
 module.exports.unSafeInnerDebugLint = nativeBinding.unSafeInnerDebugLint
 module.exports.unSafeInnerLint = nativeBinding.unSafeInnerLint
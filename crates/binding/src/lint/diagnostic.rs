@@ -1,4 +1,7 @@
-use doctor::lint::FileDiagnostic;
+use doctor::{
+  core::loc::{ColumnEncoding, LineIndex},
+  lint::FileDiagnostic,
+};
 use napi_derive::napi;
 use oxc::diagnostics::Severity;
 
@@ -21,7 +24,9 @@ impl Diagnostic {
     file_diagnostic: &FileDiagnostic,
     relative_path: &String,
     source_code: &str,
+    encoding: ColumnEncoding,
   ) -> Vec<Diagnostic> {
+    let index = LineIndex::new(source_code);
     let mut diagnostics = Vec::new();
     for diag in file_diagnostic.diagnostics.iter() {
       let code = diag.code.to_string();
@@ -41,7 +46,7 @@ impl Diagnostic {
         .as_ref()
         .map(|v| {
           v.iter()
-            .map(|l| LabeledLoc::new(&source_code, l.offset(), l.len()))
+            .map(|l| LabeledLoc::new(&index, l.offset(), l.len(), encoding))
             .collect::<Vec<_>>()
         })
         .unwrap_or_default();
@@ -1,3 +1,4 @@
+use doctor::core::loc::{ColumnEncoding, LineIndex};
 use napi_derive::napi;
 
 use super::{Location, Span};
@@ -10,11 +11,11 @@ pub struct LabeledLoc {
 }
 
 impl LabeledLoc {
-  pub fn new(source_code: &str, offset: usize, len: usize) -> Self {
+  pub fn new(index: &LineIndex, offset: usize, len: usize, encoding: ColumnEncoding) -> Self {
     let start = offset;
     let end = offset + len;
     let span = super::Span::new(offset, len);
-    let loc = super::Location::with_source(&source_code, start, end);
+    let loc = super::Location::with_index(index, start, end, encoding);
     Self { span, loc }
   }
 }
@@ -1,3 +1,4 @@
+use doctor::core::loc::{ColumnEncoding, LineIndex};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 
@@ -17,9 +18,9 @@ impl Location {
     Location { start, end }
   }
 
-  pub fn with_source(source_code: &str, start: usize, end: usize) -> Self {
-    let start = Position::with_source(source_code, start);
-    let end = Position::with_source(source_code, end);
+  pub fn with_index(index: &LineIndex, start: usize, end: usize, encoding: ColumnEncoding) -> Self {
+    let start = Position::with_index(index, start, encoding);
+    let end = Position::with_index(index, end, encoding);
     Self { start, end }
   }
 }
@@ -7,7 +7,7 @@ mod span;
 use std::collections::HashMap;
 
 pub use diagnostic::Diagnostic;
-use doctor::core::{Ignore, traits::PathExt};
+use doctor::core::{Ignore, loc::ColumnEncoding, traits::PathExt};
 use doctor::lint::{
   EnvironmentFlags, GlobalValue, Globals, LintMode, LintValidator, inner::Category20250601Inner,
 };
@@ -16,6 +16,8 @@ pub use location::Location;
 use napi::Result;
 use napi_derive::napi;
 use oxc_linter::Oxlintrc;
+
+use crate::specs::JsColumnEncoding;
 pub use position::Position;
 pub use span::Span;
 
@@ -38,6 +40,8 @@ pub struct GlobJsArgs {
   pub verbose: Option<bool>,
   pub absolute: Option<bool>,
   pub globals: Option<HashMap<String, String>>,
+  /// How the columns of `Position` are counted, defaults to `Char`
+  pub column_encoding: Option<JsColumnEncoding>,
 }
 
 fn to_napi_error<E: ToString>(e: E) -> napi::Error {
@@ -67,6 +71,8 @@ pub async fn un_safe_inner_debug_lint(
 
   let file_diagnostics = linter_runner.run().map_err(to_napi_error)?;
 
+  let encoding = ColumnEncoding::from(glob_js_args.column_encoding.unwrap_or_default());
+
   let mut diags = Vec::new();
 
   for file_diagnostic in file_diagnostics {
@@ -83,7 +89,8 @@ pub async fn un_safe_inner_debug_lint(
         file_diagnostic.file_path.to_string()
       };
 
-    let f_diags = Diagnostic::from_file_diagnostic(&file_diagnostic, &relative_path, &source_code);
+    let f_diags =
+      Diagnostic::from_file_diagnostic(&file_diagnostic, &relative_path, &source_code, encoding);
     diags.extend(f_diags);
   }
 
@@ -132,6 +139,8 @@ pub async fn un_safe_inner_lint(
 
   let file_diagnostics = linter_runner.run().map_err(to_napi_error)?;
 
+  let encoding = ColumnEncoding::from(glob_js_args.column_encoding.unwrap_or_default());
+
   let mut map = HashMap::new();
 
   let mut diags = Vec::new();
@@ -149,7 +158,8 @@ pub async fn un_safe_inner_lint(
       file_diagnostic.file_path.to_string()
     };
 
-    let f_diags = Diagnostic::from_file_diagnostic(&file_diagnostic, &relative_path, &source_code);
+    let f_diags =
+      Diagnostic::from_file_diagnostic(&file_diagnostic, &relative_path, &source_code, encoding);
     diags.extend(f_diags);
 
     for diag in file_diagnostic.diagnostics {
@@ -0,0 +1,30 @@
+use doctor::core::ChangeSource;
+use napi_derive::napi;
+
+/// Which files `git` reports as changed, the union of every option set
+#[napi(object)]
+#[derive(Clone, Debug, Default)]
+pub struct JsChangedFiles {
+  /// Files changed since this ref branched off, uncommitted changes included, e.g. `origin/main`
+  pub since: Option<String>,
+  /// Files in the index
+  pub staged: Option<bool>,
+  /// Files git does not track yet
+  pub untracked: Option<bool>,
+}
+
+impl JsChangedFiles {
+  pub fn sources(&self) -> Vec<ChangeSource> {
+    let mut sources = Vec::new();
+    if let Some(r#ref) = &self.since {
+      sources.push(ChangeSource::Since(r#ref.clone()));
+    }
+    if self.staged.unwrap_or(false) {
+      sources.push(ChangeSource::Staged);
+    }
+    if self.untracked.unwrap_or(false) {
+      sources.push(ChangeSource::Untracked);
+    }
+    sources
+  }
+}
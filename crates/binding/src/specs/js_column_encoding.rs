@@ -0,0 +1,21 @@
+use doctor::core::loc::ColumnEncoding;
+use napi_derive::napi;
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, Default)]
+pub enum JsColumnEncoding {
+  Byte,
+  #[default]
+  Char,
+  Utf16,
+}
+
+impl From<JsColumnEncoding> for ColumnEncoding {
+  fn from(encoding: JsColumnEncoding) -> Self {
+    match encoding {
+      JsColumnEncoding::Byte => ColumnEncoding::Byte,
+      JsColumnEncoding::Char => ColumnEncoding::Char,
+      JsColumnEncoding::Utf16 => ColumnEncoding::Utf16,
+    }
+  }
+}
@@ -0,0 +1,19 @@
+use doctor::core::DiagnosticCode;
+use napi_derive::napi;
+
+#[napi(object)]
+pub struct JsDiagnosticCode {
+  pub namespace: String,
+  pub validator: Option<String>,
+  pub rule: String,
+}
+
+impl From<DiagnosticCode> for JsDiagnosticCode {
+  fn from(code: DiagnosticCode) -> Self {
+    JsDiagnosticCode {
+      namespace: code.namespace,
+      validator: code.validator,
+      rule: code.rule,
+    }
+  }
+}
@@ -1,24 +1,36 @@
-use doctor::core::loc::get_source_location;
-use miette::MietteDiagnostic;
+use doctor::core::{
+  Diagnostic,
+  loc::{ColumnEncoding, LineIndex},
+  traits::DiagnosticCodeExt,
+};
 use napi_derive::napi;
 
-use super::{js_labeled_span::JsLabeledSpan, js_severity::JsSeverity};
+use super::{
+  js_diagnostic_code::JsDiagnosticCode, js_fix::JsFix, js_labeled_span::JsLabeledSpan,
+  js_severity::JsSeverity,
+};
 
 #[napi(object)]
 pub struct JsDiagnostics {
   pub message: String,
   pub code: Option<String>,
+  pub code_info: Option<JsDiagnosticCode>,
   pub severity: Option<JsSeverity>,
   pub help: Option<String>,
   pub url: Option<String>,
   pub labels: Option<Vec<JsLabeledSpan>>,
+  pub fix: Option<JsFix>,
 }
 
-impl From<(String, MietteDiagnostic)> for JsDiagnostics {
-  fn from((source_code, diagnostic): (String, MietteDiagnostic)) -> Self {
+impl JsDiagnostics {
+  pub fn new(index: &LineIndex, diagnostic: Diagnostic, encoding: ColumnEncoding) -> Self {
+    let code_info = diagnostic.diagnostic_code().map(JsDiagnosticCode::from);
+    let fix = diagnostic.fix.map(|fix| JsFix::new(fix, index, encoding));
+    let diagnostic = diagnostic.inner;
     JsDiagnostics {
       message: diagnostic.message,
       code: diagnostic.code,
+      code_info,
       severity: diagnostic.severity.map(|s| s.into()),
       help: diagnostic.help,
       url: diagnostic.url,
@@ -26,16 +38,12 @@ impl From<(String, MietteDiagnostic)> for JsDiagnostics {
         labels
           .into_iter()
           .map(|labeled_span| {
-            let loc = get_source_location(
-              source_code.clone(),
-              labeled_span.offset(),
-              labeled_span.len(),
-            );
-            (labeled_span, loc)
+            let loc = index.location(labeled_span.offset(), labeled_span.len());
+            JsLabeledSpan::new(labeled_span, loc, encoding)
           })
-          .map(JsLabeledSpan::from)
           .collect()
       }),
+      fix,
     }
   }
 }
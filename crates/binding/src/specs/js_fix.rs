@@ -0,0 +1,48 @@
+use doctor::core::{
+  Fix, TextEdit,
+  loc::{ColumnEncoding, LineIndex},
+};
+use napi_derive::napi;
+
+use super::js_location::JsSourceLocation;
+
+#[napi(object)]
+pub struct JsTextEdit {
+  /// Byte offset into the UTF-8 source, use `loc` for positions in JavaScript strings
+  pub start: u32,
+  /// Byte offset into the UTF-8 source, exclusive
+  pub end: u32,
+  pub loc: JsSourceLocation,
+  pub content: String,
+}
+
+impl JsTextEdit {
+  pub fn new(edit: TextEdit, index: &LineIndex, encoding: ColumnEncoding) -> Self {
+    let loc = index.location(edit.start, edit.end.saturating_sub(edit.start));
+    JsTextEdit {
+      start: edit.start as u32,
+      end: edit.end as u32,
+      loc: JsSourceLocation::new(loc, encoding),
+      content: edit.content,
+    }
+  }
+}
+
+#[napi(object)]
+pub struct JsFix {
+  pub message: Option<String>,
+  pub edits: Vec<JsTextEdit>,
+}
+
+impl JsFix {
+  pub fn new(fix: Fix, index: &LineIndex, encoding: ColumnEncoding) -> Self {
+    JsFix {
+      message: fix.message,
+      edits: fix
+        .edits
+        .into_iter()
+        .map(|edit| JsTextEdit::new(edit, index, encoding))
+        .collect(),
+    }
+  }
+}
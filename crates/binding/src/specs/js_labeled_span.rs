@@ -1,4 +1,4 @@
-use doctor::core::loc::Location;
+use doctor::core::loc::{ColumnEncoding, Location};
 use miette::LabeledSpan;
 use napi_derive::napi;
 
@@ -12,12 +12,12 @@ pub struct JsLabeledSpan {
   pub primary: bool,
 }
 
-impl From<(LabeledSpan, Location)> for JsLabeledSpan {
-  fn from((span, loc): (LabeledSpan, Location)) -> Self {
+impl JsLabeledSpan {
+  pub fn new(span: LabeledSpan, loc: Location, encoding: ColumnEncoding) -> Self {
     JsLabeledSpan {
       label: span.label().map(|s| s.to_string()),
       span: JsSourceSpan::from(span.inner().to_owned()),
-      loc: loc.into(),
+      loc: JsSourceLocation::new(loc, encoding),
       primary: span.primary(),
     }
   }
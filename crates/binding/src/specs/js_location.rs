@@ -1,4 +1,4 @@
-use doctor::core::loc::Location;
+use doctor::core::loc::{ColumnEncoding, Location};
 use napi_derive::napi;
 
 use super::js_position::JsSourcePosition;
@@ -10,11 +10,11 @@ pub struct JsSourceLocation {
   pub end: JsSourcePosition,
 }
 
-impl From<Location> for JsSourceLocation {
-  fn from(location: Location) -> Self {
+impl JsSourceLocation {
+  pub fn new(location: Location, encoding: ColumnEncoding) -> Self {
     JsSourceLocation {
-      start: location.start.into(),
-      end: location.end.into(),
+      start: JsSourcePosition::new(location.start, encoding),
+      end: JsSourcePosition::new(location.end, encoding),
     }
   }
 }
@@ -1,4 +1,7 @@
-use doctor::core::Messages;
+use doctor::core::{
+  Messages,
+  loc::{ColumnEncoding, LineIndex},
+};
 use napi_derive::napi;
 
 use super::js_diagnostics::JsDiagnostics;
@@ -7,20 +10,24 @@ use super::js_diagnostics::JsDiagnostics;
 pub struct JsMessages {
   // pub source_code: String,
   pub source_path: String,
+  pub validator: Option<String>,
+  /// Name of the workspace package the file belongs to, `None` at the root or outside a monorepo
+  pub package: Option<String>,
   pub diagnostics: Vec<JsDiagnostics>,
 }
 
-impl From<Messages> for JsMessages {
-  fn from(messages: Messages) -> Self {
-    let source_code = messages.source_code.clone();
+impl JsMessages {
+  pub fn new(messages: Messages, encoding: ColumnEncoding) -> Self {
+    let index = LineIndex::new(&messages.source_code);
     JsMessages {
       // source_code: messages.source_code,
       source_path: messages.source_path,
+      validator: messages.validator,
+      package: messages.package,
       diagnostics: messages
         .diagnostics
         .into_iter()
-        .map(|d| (source_code.clone(), d))
-        .map(JsDiagnostics::from)
+        .map(|d| JsDiagnostics::new(&index, d, encoding))
         .collect(),
     }
   }
@@ -0,0 +1,35 @@
+use doctor::core::{FilePatch, Patch};
+use napi_derive::napi;
+
+#[napi(object)]
+pub struct JsFilePatch {
+  pub path: String,
+  pub is_new: bool,
+  pub diff: String,
+}
+
+impl From<&FilePatch> for JsFilePatch {
+  fn from(file: &FilePatch) -> Self {
+    JsFilePatch {
+      path: file.path.to_string_lossy().to_string(),
+      is_new: file.is_new,
+      diff: file.unified_diff(),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct JsPatch {
+  pub files: Vec<JsFilePatch>,
+  /// All file diffs concatenated, can be applied with `git apply`
+  pub patch: String,
+}
+
+impl From<Patch> for JsPatch {
+  fn from(patch: Patch) -> Self {
+    JsPatch {
+      files: patch.iter().map(JsFilePatch::from).collect(),
+      patch: patch.to_string(),
+    }
+  }
+}
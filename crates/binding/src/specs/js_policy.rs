@@ -0,0 +1,63 @@
+use doctor::core::{PackageJsonPolicy, Policy, PrivatePolicy};
+use napi_derive::napi;
+
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy)]
+pub enum JsPrivatePolicy {
+  Off,
+  Exist,
+  True,
+  False,
+}
+
+impl From<JsPrivatePolicy> for PrivatePolicy {
+  fn from(policy: JsPrivatePolicy) -> Self {
+    match policy {
+      JsPrivatePolicy::Off => PrivatePolicy::Off,
+      JsPrivatePolicy::Exist => PrivatePolicy::Exist,
+      JsPrivatePolicy::True => PrivatePolicy::True,
+      JsPrivatePolicy::False => PrivatePolicy::False,
+    }
+  }
+}
+
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct JsPackageJsonPolicy {
+  pub name: Option<bool>,
+  pub private: Option<JsPrivatePolicy>,
+  pub package_manager: Option<bool>,
+  pub shineout_version: Option<bool>,
+}
+
+impl From<JsPackageJsonPolicy> for PackageJsonPolicy {
+  fn from(policy: JsPackageJsonPolicy) -> Self {
+    PackageJsonPolicy {
+      name: policy.name,
+      private: policy.private.map(PrivatePolicy::from),
+      package_manager: policy.package_manager,
+      shineout_version: policy.shineout_version,
+    }
+  }
+}
+
+/// Overrides the `policy` of spec.json field by field
+#[napi(object)]
+#[derive(Clone, Debug)]
+pub struct JsPolicy {
+  /// Ranges `.node-version` must satisfy one of, empty skips the range check
+  pub node_versions: Option<Vec<String>>,
+  /// Registries `.npmrc` may point at, empty skips the registry check
+  pub registries: Option<Vec<String>>,
+  pub package_json: Option<JsPackageJsonPolicy>,
+}
+
+impl From<JsPolicy> for Policy {
+  fn from(policy: JsPolicy) -> Self {
+    Policy {
+      node_versions: policy.node_versions,
+      registries: policy.registries,
+      package_json: policy.package_json.map(PackageJsonPolicy::from),
+    }
+  }
+}
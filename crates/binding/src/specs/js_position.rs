@@ -1,4 +1,4 @@
-use doctor::core::loc::Position;
+use doctor::core::loc::{ColumnEncoding, Position};
 use napi_derive::napi;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -8,11 +8,11 @@ pub struct JsSourcePosition {
   pub col: u32,
 }
 
-impl From<Position> for JsSourcePosition {
-  fn from(position: Position) -> Self {
+impl JsSourcePosition {
+  pub fn new(position: Position, encoding: ColumnEncoding) -> Self {
     JsSourcePosition {
       row: position.row as u32,
-      col: position.col as u32,
+      col: position.column(encoding) as u32,
     }
   }
 }
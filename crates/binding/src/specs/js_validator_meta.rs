@@ -0,0 +1,22 @@
+use doctor::core::ValidatorMeta;
+use napi_derive::napi;
+
+#[napi(object)]
+pub struct JsValidatorMeta {
+  pub id: String,
+  pub description: String,
+  /// The codes the validator can emit
+  pub codes: Vec<String>,
+  pub fixable: bool,
+}
+
+impl From<ValidatorMeta> for JsValidatorMeta {
+  fn from(meta: ValidatorMeta) -> Self {
+    JsValidatorMeta {
+      id: meta.id,
+      description: meta.description,
+      codes: meta.codes.iter().map(|code| code.to_string()).collect(),
+      fixable: meta.fixable,
+    }
+  }
+}
@@ -0,0 +1,49 @@
+use doctor::{
+  core::loc::ColumnEncoding,
+  specs::{MessagesDelta, WatchDelta},
+};
+use napi_derive::napi;
+
+use super::js_messages::JsMessages;
+
+#[napi(object)]
+pub struct JsMessagesDelta {
+  pub validator: String,
+  pub source_path: String,
+  /// Replaces what was reported before for `validator` and `sourcePath`, empty when the file is clean now or gone
+  pub messages: Vec<JsMessages>,
+}
+
+impl JsMessagesDelta {
+  pub fn new(delta: MessagesDelta, encoding: ColumnEncoding) -> Self {
+    JsMessagesDelta {
+      validator: delta.validator,
+      source_path: delta.source_path,
+      messages: delta
+        .messages
+        .into_iter()
+        .map(|messages| JsMessages::new(messages, encoding))
+        .collect(),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct JsWatchDelta {
+  /// The validators that ran in this round
+  pub validators: Vec<String>,
+  pub changes: Vec<JsMessagesDelta>,
+}
+
+impl JsWatchDelta {
+  pub fn new(delta: WatchDelta, encoding: ColumnEncoding) -> Self {
+    JsWatchDelta {
+      validators: delta.validators,
+      changes: delta
+        .changes
+        .into_iter()
+        .map(|change| JsMessagesDelta::new(change, encoding))
+        .collect(),
+    }
+  }
+}
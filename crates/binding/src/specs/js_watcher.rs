@@ -0,0 +1,27 @@
+use doctor::specs::WatchHandle;
+use napi_derive::napi;
+
+/// A running `JsSpecifications.watch`, keeps the process alive until closed
+#[napi]
+pub struct JsWatcher {
+  handle: Option<WatchHandle>,
+}
+
+impl JsWatcher {
+  pub fn new(handle: WatchHandle) -> Self {
+    JsWatcher {
+      handle: Some(handle),
+    }
+  }
+}
+
+#[napi]
+impl JsWatcher {
+  /// Stop watching, waits for a running validation to finish
+  #[napi]
+  pub fn close(&mut self) {
+    if let Some(handle) = self.handle.take() {
+      handle.stop();
+    }
+  }
+}
@@ -1,17 +1,35 @@
-use doctor::core::{Messages, ValidatorError};
+use doctor::core::{ChangedFiles, Messages, ValidatorError, loc::ColumnEncoding};
 use js_messages::JsMessages;
-use napi::Result;
+use js_patch::JsPatch;
+use js_validator_meta::JsValidatorMeta;
+use js_watch_delta::JsWatchDelta;
+use js_watcher::JsWatcher;
+use napi::{
+  Result,
+  threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode},
+};
 use napi_derive::napi;
 
 use crate::specs::raw_specifications_render_opts::RawSpecificationsRenderOpts;
 
+pub(crate) use js_column_encoding::JsColumnEncoding;
+
+mod js_changed_files;
+mod js_column_encoding;
+mod js_diagnostic_code;
 mod js_diagnostics;
+mod js_fix;
 mod js_labeled_span;
 mod js_location;
 mod js_messages;
+mod js_patch;
+mod js_policy;
 mod js_position;
 mod js_severity;
 mod js_source_span;
+mod js_validator_meta;
+mod js_watch_delta;
+mod js_watcher;
 mod raw_specifications_render_opts;
 
 #[napi]
@@ -28,8 +46,21 @@ impl JsSpecifications {
     napi::Error::new(napi::Status::GenericFailure, err.to_string())
   }
 
-  fn convert_messages(messages: Vec<Messages>) -> Vec<JsMessages> {
-    messages.into_iter().map(JsMessages::from).collect()
+  fn encoding(&self) -> ColumnEncoding {
+    let encoding = self
+      .opts
+      .as_ref()
+      .and_then(|opts| opts.column_encoding)
+      .unwrap_or_default();
+    ColumnEncoding::from(encoding)
+  }
+
+  fn convert_messages(&self, messages: Vec<Messages>) -> Vec<JsMessages> {
+    let encoding = self.encoding();
+    messages
+      .into_iter()
+      .map(|messages| JsMessages::new(messages, encoding))
+      .collect()
   }
 
   fn render_messages(&self, messages: &Vec<Messages>) {
@@ -48,16 +79,77 @@ impl JsSpecifications {
 #[napi]
 impl JsSpecifications {
   #[napi(factory)]
-  pub fn create(cwd: String, opts: Option<RawSpecificationsRenderOpts>) -> JsSpecifications {
-    let standards = doctor::specs::Specifications::create(cwd);
-    JsSpecifications { standards, opts }
+  pub fn create(
+    cwd: String,
+    opts: Option<RawSpecificationsRenderOpts>,
+  ) -> Result<JsSpecifications> {
+    let cache = opts.as_ref().and_then(|opts| opts.cache).unwrap_or(true);
+    let changed = opts.as_ref().and_then(|opts| opts.changed.as_ref());
+
+    let mut standards = doctor::specs::Specifications::create(cwd.clone()).with_cache(cache);
+    if let Some(policy) = opts.as_ref().and_then(|opts| opts.policy.clone()) {
+      standards = standards.with_policy(policy.into());
+    }
+    if let Some(changed) = changed {
+      let files = ChangedFiles::from_git(&cwd, &changed.sources()).map_err(Self::to_napi_error)?;
+      standards = standards.with_files(files);
+    }
+
+    Ok(JsSpecifications { standards, opts })
   }
 
   #[napi]
   pub async fn fix_lint(&self) -> Result<Vec<JsMessages>> {
     let res = self.standards.fix_lint().map_err(Self::to_napi_error)?;
     self.render_messages(&res);
-    Ok(Self::convert_messages(res))
+    Ok(self.convert_messages(res))
+  }
+
+  #[napi]
+  pub async fn fix_lint_dry_run(&self) -> Result<JsPatch> {
+    let res = self
+      .standards
+      .fix_lint_dry_run()
+      .map_err(Self::to_napi_error)?;
+    Ok(JsPatch::from(res))
+  }
+
+  /// Snapshot the current problems into `.sfconfig/baseline.json`, returns the number of entries
+  #[napi]
+  pub async fn update_baseline(&self) -> Result<u32> {
+    let baseline = self
+      .standards
+      .update_baseline()
+      .map_err(Self::to_napi_error)?;
+    Ok(baseline.entries.len() as u32)
+  }
+
+  /// Every available check with the codes it can emit
+  #[napi]
+  pub fn list_validators(&self) -> Result<Vec<JsValidatorMeta>> {
+    let metas = self
+      .standards
+      .list_validators()
+      .map_err(Self::to_napi_error)?;
+    Ok(metas.into_iter().map(JsValidatorMeta::from).collect())
+  }
+
+  /// spec.json with its `extends` resolved and the effective policy, as JSON
+  #[napi]
+  pub fn print_config(&self) -> Result<String> {
+    self.standards.print_config().map_err(Self::to_napi_error)
+  }
+
+  #[napi]
+  pub async fn validate_sfconfig(&self) -> Result<Vec<JsMessages>> {
+    let res = self
+      .standards
+      .validate_sfconfig()
+      .map_err(Self::to_napi_error)?;
+
+    self.render_messages(&res);
+
+    Ok(self.convert_messages(res))
   }
 
   #[napi]
@@ -69,7 +161,7 @@ impl JsSpecifications {
 
     self.render_messages(&res);
 
-    Ok(Self::convert_messages(res))
+    Ok(self.convert_messages(res))
   }
 
   #[napi]
@@ -81,7 +173,7 @@ impl JsSpecifications {
 
     self.render_messages(&res);
 
-    Ok(Self::convert_messages(res))
+    Ok(self.convert_messages(res))
   }
 
   #[napi]
@@ -93,7 +185,7 @@ impl JsSpecifications {
 
     self.render_messages(&res);
 
-    Ok(Self::convert_messages(res))
+    Ok(self.convert_messages(res))
   }
 
   #[napi]
@@ -105,7 +197,7 @@ impl JsSpecifications {
 
     self.render_messages(&res);
 
-    Ok(Self::convert_messages(res))
+    Ok(self.convert_messages(res))
   }
 
   #[napi]
@@ -117,7 +209,23 @@ impl JsSpecifications {
 
     self.render_messages(&res);
 
-    Ok(Self::convert_messages(res))
+    Ok(self.convert_messages(res))
+  }
+
+  /// Validate everything, then again whenever files under `cwd` change
+  ///
+  /// `callback` first gets every file with diagnostics, then only the files whose messages changed.
+  #[napi]
+  pub fn watch(&self, callback: ThreadsafeFunction<JsWatchDelta>) -> Result<JsWatcher> {
+    let encoding = self.encoding();
+    let handle = self
+      .standards
+      .watch(Default::default(), move |delta| {
+        let delta = JsWatchDelta::new(delta, encoding);
+        callback.call(Ok(delta), ThreadsafeFunctionCallMode::NonBlocking);
+      })
+      .map_err(Self::to_napi_error)?;
+    Ok(JsWatcher::new(handle))
   }
 
   #[napi]
@@ -126,6 +234,12 @@ impl JsSpecifications {
 
     self.render_messages(&res);
 
-    Ok(Self::convert_messages(res))
+    Ok(self.convert_messages(res))
   }
 }
+
+/// The JSON Schema of `.sfconfig/spec.json`, for editors to complete and check the file
+#[napi]
+pub fn sfconfig_schema() -> String {
+  doctor::specs::Specifications::sfconfig_schema()
+}
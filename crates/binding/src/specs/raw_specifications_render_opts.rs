@@ -1,12 +1,24 @@
 use doctor::specs::SpecificationsRenderOpts;
 use napi_derive::napi;
 
+use super::{
+  js_changed_files::JsChangedFiles, js_column_encoding::JsColumnEncoding, js_policy::JsPolicy,
+};
+
 #[napi(object)]
 #[derive(Clone, Debug)]
 pub struct RawSpecificationsRenderOpts {
   pub with_dashboard: Option<bool>,
   pub max_render_count: Option<u32>,
   pub quiet: Option<bool>,
+  /// How the columns of `JsSourcePosition` are counted, defaults to `Char`
+  pub column_encoding: Option<JsColumnEncoding>,
+  /// Reuse lint and syntax results of unchanged files, defaults to `true`
+  pub cache: Option<bool>,
+  /// Only check the files `git` reports as changed, config files included only when changed
+  pub changed: Option<JsChangedFiles>,
+  /// Overrides the `policy` of spec.json field by field
+  pub policy: Option<JsPolicy>,
 }
 
 impl Default for RawSpecificationsRenderOpts {
@@ -15,6 +27,10 @@ impl Default for RawSpecificationsRenderOpts {
       with_dashboard: Some(true),
       max_render_count: None,
       quiet: Some(false),
+      column_encoding: None,
+      cache: Some(true),
+      changed: None,
+      policy: None,
     }
   }
 }
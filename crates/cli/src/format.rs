@@ -0,0 +1,38 @@
+use clap::ValueEnum;
+use doctor::specs::ReportFormat;
+
+/// `--format` of the checking commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+  /// Graphical reports and a summary table, for people
+  Pretty,
+  Checkstyle,
+  Github,
+  Gitlab,
+  Json,
+  Junit,
+  Sarif,
+}
+
+impl OutputFormat {
+  /// `None` for [`OutputFormat::Pretty`], which is rendered instead of reported
+  pub fn report_format(self) -> Option<ReportFormat> {
+    match self {
+      OutputFormat::Pretty => None,
+      OutputFormat::Checkstyle => Some(ReportFormat::Checkstyle),
+      OutputFormat::Github => Some(ReportFormat::Github),
+      OutputFormat::Gitlab => Some(ReportFormat::Gitlab),
+      OutputFormat::Json => Some(ReportFormat::Json),
+      OutputFormat::Junit => Some(ReportFormat::Junit),
+      OutputFormat::Sarif => Some(ReportFormat::Sarif),
+    }
+  }
+}
+
+/// `--format` of `doctor cloc`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ClocFormat {
+  /// A table sorted by lines of code
+  Pretty,
+  Json,
+}
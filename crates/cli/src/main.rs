@@ -0,0 +1,342 @@
+use std::{
+  path::{Path, PathBuf},
+  process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand};
+use doctor::{
+  cloc::Opts,
+  core::{ChangeSource, ChangedFiles, Ignore, Messages, ValidatorError},
+  specs::{ConsoleWriter, Specifications, SpecificationsRenderOpts, ValidatorOutcome},
+};
+use env_logger::{Builder, Env};
+use tabled::{Table, Tabled};
+
+mod format;
+mod summary;
+
+use format::{ClocFormat, OutputFormat};
+use summary::{EXIT_FAILURE, EXIT_OK, Summary, only_errors};
+
+#[derive(Parser)]
+#[command(name = "doctor", version, about = "Check project health")]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+
+  /// Project root, defaults to the current directory
+  #[arg(long, global = true, value_name = "PATH")]
+  cwd: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Run every validator: spec.json, .npmrc, .node-version, package.json, lint and syntax
+  Check(CheckArgs),
+  /// Apply the fixes of lint, .npmrc, .node-version and package.json, then report what is left
+  Fix {
+    #[command(flatten)]
+    check: CheckArgs,
+
+    /// Print the fixes as a unified diff instead of writing them
+    #[arg(long)]
+    dry_run: bool,
+  },
+  /// Only run the linter
+  Lint(CheckArgs),
+  /// Only check that the sources parse with the configured syntax
+  Syntax(CheckArgs),
+  /// Count lines of code, comments and blanks per language
+  Cloc {
+    /// Files or directories to count, defaults to `--cwd`
+    paths: Vec<PathBuf>,
+
+    /// Extra gitignore style patterns to skip, may be repeated
+    #[arg(long, value_name = "PATTERN")]
+    ignore: Vec<String>,
+
+    #[arg(long, value_enum, default_value_t = ClocFormat::Pretty)]
+    format: ClocFormat,
+  },
+  /// Print .sfconfig/spec.json with extends resolved and the effective policy
+  PrintConfig,
+}
+
+#[derive(Args)]
+struct CheckArgs {
+  #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+  format: OutputFormat,
+
+  /// Exit with 1 when there are more warnings than this
+  #[arg(long, value_name = "COUNT")]
+  max_warnings: Option<usize>,
+
+  /// Only report errors, warnings still count towards `--max-warnings`
+  #[arg(long, short)]
+  quiet: bool,
+
+  /// Ignore `.sfconfig/baseline.json`
+  #[arg(long)]
+  no_baseline: bool,
+
+  /// Do not reuse lint and syntax results of unchanged files
+  #[arg(long)]
+  no_cache: bool,
+
+  #[command(flatten)]
+  changed: ChangedArgs,
+}
+
+/// Only check the files `git` reports as changed, the union of every option set
+#[derive(Args)]
+struct ChangedArgs {
+  /// Files changed since this ref branched off, uncommitted changes included, e.g. `origin/main`
+  #[arg(long, value_name = "REF")]
+  since: Option<String>,
+
+  /// Files in the index
+  #[arg(long)]
+  staged: bool,
+
+  /// Files git does not track yet
+  #[arg(long)]
+  untracked: bool,
+}
+
+impl ChangedArgs {
+  fn sources(&self) -> Vec<ChangeSource> {
+    let mut sources = Vec::new();
+    if let Some(r#ref) = &self.since {
+      sources.push(ChangeSource::Since(r#ref.clone()));
+    }
+    if self.staged {
+      sources.push(ChangeSource::Staged);
+    }
+    if self.untracked {
+      sources.push(ChangeSource::Untracked);
+    }
+    sources
+  }
+}
+
+#[derive(Tabled)]
+struct ClocRow {
+  #[tabled(rename = "language")]
+  language: String,
+  #[tabled(rename = "code")]
+  code: u32,
+  #[tabled(rename = "comments")]
+  comments: u32,
+  #[tabled(rename = "blanks")]
+  blanks: u32,
+}
+
+fn specifications(cwd: &Path, args: &CheckArgs) -> Result<Specifications, ValidatorError> {
+  let mut specifications = Specifications::create(cwd.to_string_lossy().to_string())
+    .with_baseline(!args.no_baseline)
+    .with_cache(!args.no_cache);
+
+  let sources = args.changed.sources();
+  if !sources.is_empty() {
+    specifications = specifications.with_files(ChangedFiles::from_git(cwd, &sources)?);
+  }
+
+  Ok(specifications)
+}
+
+/// Print `messages` in `--format` and turn them into the exit code
+fn report(specifications: &Specifications, messages: Vec<Messages>, args: &CheckArgs) -> ExitCode {
+  let summary = Summary::new(&messages);
+  let messages = if args.quiet {
+    only_errors(messages)
+  } else {
+    messages
+  };
+
+  let mut writer = ConsoleWriter;
+  match args.format.report_format() {
+    Some(format) => specifications.report_with_writer(&messages, format, &mut writer),
+    None => {
+      specifications.render_with_writer(
+        &messages,
+        &mut writer,
+        SpecificationsRenderOpts::default(),
+      );
+      // 机器可读的格式要保持 stdout 干净，汇总只在 pretty 下输出
+      if summary != Summary::default() {
+        eprintln!("{summary}");
+      }
+    }
+  }
+
+  summary.exit_code(args.max_warnings)
+}
+
+/// Like [`report`] for a run of every validator, one that crashed fails the run
+fn report_outcomes(
+  specifications: &Specifications,
+  outcomes: Vec<ValidatorOutcome>,
+  args: &CheckArgs,
+) -> ExitCode {
+  let crashed = outcomes.iter().any(ValidatorOutcome::is_crashed);
+  let messages = outcomes
+    .into_iter()
+    .flat_map(|outcome| outcome.messages)
+    .collect();
+
+  let code = report(specifications, messages, args);
+  if crashed {
+    ExitCode::from(EXIT_FAILURE)
+  } else {
+    code
+  }
+}
+
+fn cloc(cwd: &Path, paths: Vec<PathBuf>, ignore: Vec<String>, format: ClocFormat) -> ExitCode {
+  let paths = if paths.is_empty() {
+    vec![cwd.to_path_buf()]
+  } else {
+    paths.into_iter().map(|path| cwd.join(path)).collect()
+  };
+  let opts = Opts {
+    ignore: Ignore::from(ignore),
+  };
+
+  let rows = doctor::cloc::cloc(&paths, Some(opts))
+    .into_iter()
+    .map(|stats| ClocRow {
+      language: stats.lang.name().to_string(),
+      code: stats.code,
+      comments: stats.comments,
+      blanks: stats.blanks,
+    })
+    .collect::<Vec<_>>();
+
+  match format {
+    ClocFormat::Pretty => println!("{}", Table::new(rows)),
+    ClocFormat::Json => {
+      let rows = rows
+        .iter()
+        .map(|row| {
+          serde_json::json!({
+            "lang": row.language,
+            "code": row.code,
+            "comments": row.comments,
+            "blanks": row.blanks,
+          })
+        })
+        .collect::<Vec<_>>();
+      println!("{}", serde_json::Value::Array(rows));
+    }
+  }
+
+  ExitCode::from(EXIT_OK)
+}
+
+fn run(cli: Cli) -> Result<ExitCode, ValidatorError> {
+  let cwd = match cli.cwd {
+    Some(cwd) => cwd,
+    None => std::env::current_dir()?,
+  };
+
+  match cli.command {
+    Command::Check(args) => {
+      let specifications = specifications(&cwd, &args)?;
+      let outcomes = specifications.run_all()?;
+      Ok(report_outcomes(&specifications, outcomes, &args))
+    }
+    Command::Fix {
+      check: args,
+      dry_run,
+    } => {
+      let specifications = specifications(&cwd, &args)?;
+      if dry_run {
+        print!("{}", specifications.fix_all_dry_run()?);
+        return Ok(ExitCode::from(EXIT_OK));
+      }
+      let outcomes = specifications.fix_all()?;
+      Ok(report_outcomes(&specifications, outcomes, &args))
+    }
+    Command::Lint(args) => {
+      let specifications = specifications(&cwd, &args)?;
+      let messages = specifications.validate_lint()?;
+      Ok(report(&specifications, messages, &args))
+    }
+    Command::Syntax(args) => {
+      let specifications = specifications(&cwd, &args)?;
+      let messages = specifications.validate_syntax()?;
+      Ok(report(&specifications, messages, &args))
+    }
+    Command::Cloc {
+      paths,
+      ignore,
+      format,
+    } => Ok(cloc(&cwd, paths, ignore, format)),
+    Command::PrintConfig => {
+      let specifications = Specifications::create(cwd.to_string_lossy().to_string());
+      println!("{}", specifications.print_config()?);
+      Ok(ExitCode::from(EXIT_OK))
+    }
+  }
+}
+
+fn main() -> ExitCode {
+  Builder::from_env(Env::default().default_filter_or("error"))
+    .format_timestamp(None)
+    .init();
+
+  // 参数错误由 clap 打印并以 2 退出
+  let cli = Cli::parse();
+
+  match run(cli) {
+    Ok(code) => code,
+    Err(e) => {
+      eprintln!("doctor: {e}");
+      ExitCode::from(EXIT_FAILURE)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::CommandFactory;
+
+  use super::*;
+
+  #[test]
+  fn should_define_a_valid_command() {
+    Cli::command().debug_assert();
+  }
+
+  #[test]
+  fn should_parse_check_options() {
+    let cli = Cli::try_parse_from([
+      "doctor",
+      "check",
+      "--format",
+      "json",
+      "--max-warnings",
+      "0",
+      "--since",
+      "origin/main",
+      "--staged",
+      "--cwd",
+      "/repo",
+    ])
+    .unwrap();
+
+    assert_eq!(cli.cwd, Some(PathBuf::from("/repo")));
+    let Command::Check(args) = cli.command else {
+      panic!("expected check");
+    };
+    assert_eq!(args.format, OutputFormat::Json);
+    assert_eq!(args.max_warnings, Some(0));
+    assert_eq!(
+      args.changed.sources(),
+      vec![
+        ChangeSource::Since("origin/main".to_string()),
+        ChangeSource::Staged
+      ]
+    );
+  }
+}
@@ -0,0 +1,114 @@
+use std::process::ExitCode;
+
+use doctor::core::{JsonSeverity, Messages};
+
+/// Nothing to report, or only warnings within `--max-warnings`
+pub const EXIT_OK: u8 = 0;
+/// Errors were found, or more warnings than `--max-warnings`
+pub const EXIT_PROBLEMS: u8 = 1;
+/// doctor could not run or a validator crashed, e.g. an unreadable spec.json or a failing `git`
+pub const EXIT_FAILURE: u8 = 2;
+
+/// Diagnostic counts of one run, see [`Summary::exit_code`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+  pub errors: usize,
+  pub warnings: usize,
+}
+
+impl Summary {
+  pub fn new(messages: &[Messages]) -> Self {
+    let mut summary = Self::default();
+    for diagnostic in messages.iter().flat_map(|messages| messages.iter()) {
+      match JsonSeverity::from(diagnostic.severity) {
+        JsonSeverity::Error => summary.errors += 1,
+        JsonSeverity::Warning => summary.warnings += 1,
+        JsonSeverity::Advice => {}
+      }
+    }
+    summary
+  }
+
+  /// Like eslint, warnings only fail the run when there are more than `max_warnings`
+  pub fn exit_code(&self, max_warnings: Option<usize>) -> ExitCode {
+    let too_many_warnings = max_warnings.is_some_and(|max| self.warnings > max);
+    if self.errors > 0 || too_many_warnings {
+      ExitCode::from(EXIT_PROBLEMS)
+    } else {
+      ExitCode::from(EXIT_OK)
+    }
+  }
+}
+
+impl std::fmt::Display for Summary {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Found {} errors and {} warnings",
+      self.errors, self.warnings
+    )
+  }
+}
+
+/// Drop everything but errors, for `--quiet`
+pub fn only_errors(messages: Vec<Messages>) -> Vec<Messages> {
+  messages
+    .into_iter()
+    .map(|mut messages| {
+      messages
+        .diagnostics
+        .retain(|diagnostic| JsonSeverity::from(diagnostic.severity) == JsonSeverity::Error);
+      messages
+    })
+    .filter(|messages| !messages.is_empty())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn messages(severities: &[miette::Severity]) -> Vec<Messages> {
+    let diagnostics = severities
+      .iter()
+      .map(|severity| {
+        doctor::core::Diagnostic::from(
+          miette::MietteDiagnostic::new("message")
+            .with_code("eslint(no-debugger)")
+            .with_severity(*severity),
+        )
+      })
+      .collect();
+    vec![Messages::builder().diagnostics(diagnostics).build()]
+  }
+
+  #[test]
+  fn should_fail_on_errors_and_too_many_warnings() {
+    use miette::Severity::*;
+
+    let summary = Summary::new(&messages(&[Warning, Warning, Advice]));
+    assert_eq!(
+      summary,
+      Summary {
+        errors: 0,
+        warnings: 2
+      }
+    );
+    assert_eq!(summary.exit_code(None), ExitCode::from(EXIT_OK));
+    assert_eq!(summary.exit_code(Some(2)), ExitCode::from(EXIT_OK));
+    assert_eq!(summary.exit_code(Some(1)), ExitCode::from(EXIT_PROBLEMS));
+
+    let summary = Summary::new(&messages(&[Error]));
+    assert_eq!(summary.exit_code(None), ExitCode::from(EXIT_PROBLEMS));
+  }
+
+  #[test]
+  fn should_keep_only_errors_when_quiet() {
+    use miette::Severity::*;
+
+    let quiet = only_errors(messages(&[Error, Warning]));
+    assert_eq!(quiet[0].diagnostics.len(), 1);
+
+    assert!(only_errors(messages(&[Warning, Advice])).is_empty());
+  }
+}
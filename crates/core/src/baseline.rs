@@ -0,0 +1,315 @@
+use std::{
+  collections::{BTreeSet, HashMap},
+  fs,
+  path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  Diagnostic, DiagnosticCode, Messages, ValidatorError, fingerprint, fingerprint_of,
+  traits::DiagnosticCodeExt,
+};
+
+/// Bumped whenever the layout of the baseline file changes
+pub const BASELINE_VERSION: u32 = 1;
+
+/// A known problem, identified by its code, file and [`fingerprint`] rather than its line
+///
+/// Unlike [`fingerprint`], absolute paths under the root are left out of messages without a
+/// label, and a label over the whole file is not hashed, so any edit would change it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineEntry {
+  pub validator: String,
+  pub code: DiagnosticCode,
+  pub path: String,
+  pub fingerprint: String,
+}
+
+/// A snapshot of known problems, later runs only report diagnostics that are not in it
+///
+/// Entries form a multiset, two identical problems in one file need two entries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Baseline {
+  pub version: u32,
+  pub entries: Vec<BaselineEntry>,
+}
+
+impl Default for Baseline {
+  fn default() -> Self {
+    Self {
+      version: BASELINE_VERSION,
+      entries: Vec::new(),
+    }
+  }
+}
+
+/// The outcome of [`Baseline::filter`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BaselineResult {
+  /// Number of diagnostics dropped because they are in the baseline
+  pub suppressed: usize,
+  /// Baseline entries no longer reported, the baseline can be updated to drop them
+  pub fixed: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+  fn entries_of(messages: &Messages, root: &Path) -> Vec<BaselineEntry> {
+    let path = messages.relative_path(root);
+    messages
+      .iter()
+      .map(|diagnostic| BaselineEntry {
+        validator: messages.validator_of(diagnostic),
+        code: diagnostic.diagnostic_code_or_unknown(),
+        path: path.clone(),
+        fingerprint: Self::fingerprint(&path, root, &messages.source_code, diagnostic),
+      })
+      .collect()
+  }
+
+  fn fingerprint(path: &str, root: &Path, source_code: &str, diagnostic: &Diagnostic) -> String {
+    let code = diagnostic.diagnostic_code_or_unknown().to_string();
+    match diagnostic.primary_label() {
+      // 比如 package.json 缺字段，标的是整个文件，只按 code 和路径识别
+      Some(label) if label.offset() == 0 && label.len() >= source_code.len() => {
+        fingerprint_of(&code, path, "")
+      }
+      Some(_) => fingerprint(path, source_code, diagnostic),
+      // message 里的绝对路径随检出的目录变化
+      None => {
+        let root = root.display().to_string();
+        let message = diagnostic
+          .message
+          .replace(&format!("{root}{}", std::path::MAIN_SEPARATOR), "")
+          .replace(&root, "");
+        fingerprint_of(&code, path, &message)
+      }
+    }
+  }
+
+  /// Snapshot all diagnostics of `messages`, paths are relative to `root`
+  pub fn from_messages(messages: &[Messages], root: &Path) -> Self {
+    let mut entries = messages
+      .iter()
+      .flat_map(|messages| Self::entries_of(messages, root))
+      .collect::<Vec<_>>();
+
+    // 排序后文件内容稳定，方便 review
+    entries.sort();
+
+    Self {
+      version: BASELINE_VERSION,
+      entries,
+    }
+  }
+
+  /// Read the baseline at `path`, `None` when the file does not exist
+  pub fn read(path: impl AsRef<Path>) -> Result<Option<Self>, ValidatorError> {
+    let content = match fs::read_to_string(path) {
+      Ok(content) => content,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+      Err(e) => return Err(e.into()),
+    };
+
+    let baseline =
+      serde_json::from_str::<Self>(&content).map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
+
+    Ok(Some(baseline))
+  }
+
+  pub fn write(&self, path: impl AsRef<Path>) -> Result<(), ValidatorError> {
+    let path = path.as_ref();
+
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    let json =
+      serde_json::to_string_pretty(self).map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
+    fs::write(path, json + "\n")?;
+
+    Ok(())
+  }
+
+  /// Drop the diagnostics of `messages` that are in the baseline
+  ///
+  /// An entry counts as fixed when its validator was run, its file was checked (or no longer
+  /// exists under `root` ) and nothing matched it.
+  pub fn filter(&self, messages: &mut [Messages], root: &Path) -> BaselineResult {
+    let mut remaining: HashMap<&BaselineEntry, usize> = HashMap::new();
+    for entry in &self.entries {
+      *remaining.entry(entry).or_default() += 1;
+    }
+
+    let mut result = BaselineResult::default();
+    let mut validators = BTreeSet::new();
+    let mut paths = BTreeSet::new();
+
+    for messages in messages.iter_mut() {
+      if let Some(validator) = &messages.validator {
+        validators.insert(validator.clone());
+      }
+      paths.insert(messages.relative_path(root));
+
+      let entries = Self::entries_of(messages, root);
+      let mut entries = entries.iter();
+
+      messages.retain(|_| {
+        let Some(entry) = entries.next() else {
+          return true;
+        };
+        match remaining.get_mut(entry) {
+          Some(count) if *count > 0 => {
+            *count -= 1;
+            result.suppressed += 1;
+            false
+          }
+          _ => true,
+        }
+      });
+    }
+
+    for entry in &self.entries {
+      let Some(count) = remaining.get_mut(entry) else {
+        continue;
+      };
+      if *count == 0 || !validators.contains(&entry.validator) {
+        continue;
+      }
+      if paths.contains(&entry.path) || !root.join(&entry.path).exists() {
+        *count -= 1;
+        result.fixed.push(entry.clone());
+      }
+    }
+
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use miette::{LabeledSpan, MietteDiagnostic};
+
+  use super::*;
+  use crate::{Diagnostic, testing::messages};
+
+  fn debugger(offset: usize) -> Diagnostic {
+    Diagnostic::from(
+      MietteDiagnostic::new("`debugger` statement is not allowed")
+        .with_code("eslint(no-debugger)")
+        .with_label(LabeledSpan::at(offset..offset + 9, "")),
+    )
+  }
+
+  #[test]
+  fn should_filter_baselined_diagnostics_after_line_shift() {
+    let root = Path::new("/repo");
+    let before = messages("lint", "/repo/a.js", "debugger;\n", vec![debugger(0)]);
+    let baseline = Baseline::from_messages(&[before], root);
+
+    let mut after = vec![messages(
+      "lint",
+      "/repo/a.js",
+      "let a = 1;\ndebugger;\ndebugger;\n",
+      vec![debugger(11), debugger(21)],
+    )];
+    let result = baseline.filter(&mut after, root);
+
+    assert_eq!(result.suppressed, 1);
+    assert!(result.fixed.is_empty());
+    // 同样内容出现两次，只有一次在 baseline 里
+    assert_eq!(after[0].len(), 1);
+  }
+
+  #[test]
+  fn should_report_fixed_entries_of_checked_files() {
+    let root = Path::new("/repo");
+    let baseline = Baseline::from_messages(
+      &[
+        messages("lint", "/repo/a.js", "debugger;\n", vec![debugger(0)]),
+        messages("lint", "/repo/b.js", "debugger;\n", vec![debugger(0)]),
+      ],
+      root,
+    );
+
+    let mut after = vec![messages("lint", "/repo/a.js", "\n", vec![])];
+    let result = baseline.filter(&mut after, root);
+
+    // b.js 没有被检查，但它已经不存在了
+    assert_eq!(result.fixed.len(), 2);
+    assert_eq!(result.fixed[0].path, "a.js");
+    assert_eq!(result.fixed[1].path, "b.js");
+  }
+
+  #[test]
+  fn should_not_report_entries_of_other_validators_as_fixed() {
+    let root = Path::new("/repo");
+    let baseline = Baseline::from_messages(
+      &[messages(
+        "lint",
+        "/repo/a.js",
+        "debugger;\n",
+        vec![debugger(0)],
+      )],
+      root,
+    );
+
+    let mut after = vec![
+      Messages::builder()
+        .source_path("/repo/.npmrc".to_string())
+        .validator("npmrc")
+        .build(),
+    ];
+    let result = baseline.filter(&mut after, root);
+
+    assert!(result.fixed.is_empty());
+  }
+
+  #[test]
+  fn should_not_depend_on_root_or_file_content() {
+    let not_found = |root: &str| {
+      let diagnostic = Diagnostic::from(
+        MietteDiagnostic::new(format!("{root}/.npmrc not found")).with_code("shined(npmrc:x)"),
+      );
+      messages("lint", &format!("{root}/.npmrc"), "", vec![diagnostic])
+    };
+    let whole_file = |source: &str| {
+      let diagnostic = Diagnostic::from(
+        MietteDiagnostic::new("`name` is missing")
+          .with_code("shined(package-json:x)")
+          .with_label(LabeledSpan::at(0..source.len(), "")),
+      );
+      messages("lint", "/repo/package.json", source, vec![diagnostic])
+    };
+
+    let a = Baseline::from_messages(&[not_found("/repo")], Path::new("/repo"));
+    let b = Baseline::from_messages(&[not_found("/ci/build")], Path::new("/ci/build"));
+    assert_eq!(a, b);
+
+    let root = Path::new("/repo");
+    let a = Baseline::from_messages(&[whole_file("{}")], root);
+    let b = Baseline::from_messages(&[whole_file("{ \"private\": true }")], root);
+    assert_eq!(a, b);
+  }
+
+  #[test]
+  fn should_round_trip_through_json() {
+    let baseline = Baseline::from_messages(
+      &[messages(
+        "lint",
+        "/repo/a.js",
+        "debugger;\n",
+        vec![debugger(0)],
+      )],
+      Path::new("/repo"),
+    );
+
+    let json = serde_json::to_string(&baseline).unwrap();
+    let back: Baseline = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(back, baseline);
+    assert!(json.contains(r#""code":"eslint(no-debugger)""#));
+  }
+}
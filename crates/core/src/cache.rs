@@ -0,0 +1,312 @@
+use std::{
+  collections::HashSet,
+  fs,
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+};
+
+use miette::{LabeledSpan, MietteDiagnostic, Severity};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+
+use crate::{Diagnostic, Fix};
+
+/// Bumped whenever the layout of cache entries changes, old entries are then never hit
+const CACHE_VERSION: u32 = 1;
+
+/// A label of a [`CachedDiagnostic`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedLabel {
+  pub label: Option<String>,
+  pub offset: usize,
+  pub length: usize,
+  pub primary: bool,
+}
+
+/// A [`Diagnostic`] in a form that round-trips through serde
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedDiagnostic {
+  pub message: String,
+  pub code: Option<String>,
+  /// `error`, `warning` or `advice`, `None` when the diagnostic had no severity
+  pub severity: Option<String>,
+  pub help: Option<String>,
+  pub url: Option<String>,
+  pub labels: Vec<CachedLabel>,
+  pub fix: Option<Fix>,
+}
+
+impl From<&Diagnostic> for CachedDiagnostic {
+  fn from(diagnostic: &Diagnostic) -> Self {
+    let severity = diagnostic.severity.map(|severity| {
+      match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Advice => "advice",
+      }
+      .to_string()
+    });
+
+    let labels = diagnostic
+      .labels
+      .iter()
+      .flatten()
+      .map(|label| CachedLabel {
+        label: label.label().map(|s| s.to_string()),
+        offset: label.offset(),
+        length: label.len(),
+        primary: label.primary(),
+      })
+      .collect();
+
+    Self {
+      message: diagnostic.message.clone(),
+      code: diagnostic.code.clone(),
+      severity,
+      help: diagnostic.help.clone(),
+      url: diagnostic.url.clone(),
+      labels,
+      fix: diagnostic.fix.clone(),
+    }
+  }
+}
+
+impl From<CachedDiagnostic> for Diagnostic {
+  fn from(cached: CachedDiagnostic) -> Self {
+    let labels = cached
+      .labels
+      .into_iter()
+      .map(|label| {
+        let span = (label.offset, label.length);
+        if label.primary {
+          LabeledSpan::new_primary_with_span(label.label, span)
+        } else {
+          LabeledSpan::new_with_span(label.label, span)
+        }
+      })
+      .collect::<Vec<_>>();
+
+    let mut inner = MietteDiagnostic::new(cached.message);
+    inner.code = cached.code;
+    inner.severity = cached
+      .severity
+      .as_deref()
+      .and_then(|severity| match severity {
+        "error" => Some(Severity::Error),
+        "warning" => Some(Severity::Warning),
+        "advice" => Some(Severity::Advice),
+        _ => None,
+      });
+    inner.help = cached.help;
+    inner.url = cached.url;
+    if !labels.is_empty() {
+      inner.labels = Some(labels);
+    }
+
+    Diagnostic::from(inner).with_fix(cached.fix)
+  }
+}
+
+/// An on-disk cache of per-file results, keyed by file path, content and a salt
+///
+/// The salt should capture everything else the result depends on, e.g. a hash of the effective
+/// config and the engine version. Every entry is its own file, so concurrent readers and
+/// writers never see a partial entry. Entries that are never hit again pile up until
+/// [`ResultCache::prune`] removes them.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::ResultCache;
+///
+/// let dir = std::env::temp_dir().join("doctor-cache-doc");
+/// let cache = ResultCache::new(&dir, "config-hash@0.1.0");
+///
+/// cache.put("src/a.js", "debugger;", &vec![1, 2, 3]).unwrap();
+///
+/// assert_eq!(cache.get::<Vec<i32>>("src/a.js", "debugger;"), Some(vec![1, 2, 3]));
+/// assert_eq!(cache.get::<Vec<i32>>("src/a.js", "debugger;\n"), None);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResultCache {
+  dir: PathBuf,
+  salt: String,
+  /// Keys read or written so far, shared by the clones
+  used: Arc<Mutex<HashSet<String>>>,
+}
+
+impl ResultCache {
+  pub fn new(dir: impl AsRef<Path>, salt: impl Into<String>) -> Self {
+    Self {
+      dir: dir.as_ref().to_path_buf(),
+      salt: salt.into(),
+      used: Arc::default(),
+    }
+  }
+
+  pub fn dir(&self) -> &Path {
+    &self.dir
+  }
+
+  fn key(&self, path: &str, source_code: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(CACHE_VERSION.to_le_bytes());
+    hasher.update(self.salt.as_bytes());
+    hasher.update([0]);
+    hasher.update(path.as_bytes());
+    hasher.update([0]);
+    hasher.update(source_code.as_bytes());
+    format!("{:x}", hasher.finalize())
+  }
+
+  fn mark_used(&self, key: String) {
+    if let Ok(mut used) = self.used.lock() {
+      used.insert(key);
+    }
+  }
+
+  fn entry_path(&self, key: &str) -> PathBuf {
+    // 按前两位分目录，避免单个目录下文件过多
+    self.dir.join(&key[..2]).join(format!("{}.json", key))
+  }
+
+  /// The cached value for `path` with `source_code`, `None` on a miss or an unreadable entry
+  pub fn get<T: DeserializeOwned>(&self, path: &str, source_code: &str) -> Option<T> {
+    let key = self.key(path, source_code);
+    let content = fs::read_to_string(self.entry_path(&key)).ok()?;
+    let value = serde_json::from_str(&content).ok()?;
+    self.mark_used(key);
+    Some(value)
+  }
+
+  pub fn put<T: Serialize>(&self, path: &str, source_code: &str, value: &T) -> std::io::Result<()> {
+    let key = self.key(path, source_code);
+    let entry = self.entry_path(&key);
+
+    if !self.dir.exists() {
+      fs::create_dir_all(&self.dir)?;
+      // 缓存目录不应该被提交
+      fs::write(self.dir.join(".gitignore"), "*\n")?;
+    }
+    if let Some(parent) = entry.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    let json = serde_json::to_string(value).map_err(std::io::Error::other)?;
+
+    // 先写临时文件再 rename，读者不会看到写了一半的内容
+    let tmp = entry.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, &entry)?;
+    self.mark_used(key);
+    Ok(())
+  }
+
+  /// Remove every entry this cache did not hit or write, returns how many were removed
+  ///
+  /// Only call it after a run that looked up every file, the entries of deleted files, old
+  /// contents and old configs are then the ones left.
+  pub fn prune(&self) -> std::io::Result<usize> {
+    let used = self
+      .used
+      .lock()
+      .map_err(|_| std::io::Error::other("cache lock poisoned"))?;
+
+    let shards = match fs::read_dir(&self.dir) {
+      Ok(shards) => shards,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+      Err(e) => return Err(e),
+    };
+
+    let mut removed = 0;
+    for shard in shards {
+      let shard = shard?.path();
+      if !shard.is_dir() {
+        continue;
+      }
+      for entry in fs::read_dir(&shard)? {
+        let entry = entry?.path();
+        let is_unused = entry
+          .extension()
+          .is_some_and(|extension| extension == "json")
+          && entry
+            .file_stem()
+            .is_some_and(|key| !used.contains(key.to_string_lossy().as_ref()));
+        if is_unused {
+          fs::remove_file(&entry)?;
+          removed += 1;
+        }
+      }
+      // 目录里还有别的进程正在写的文件时删不掉，留着即可
+      let _ = fs::remove_dir(&shard);
+    }
+
+    Ok(removed)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::TextEdit;
+
+  use super::*;
+
+  #[test]
+  fn should_round_trip_diagnostic() {
+    let diagnostic = Diagnostic::from(
+      MietteDiagnostic::new("`debugger` statement is not allowed")
+        .with_code("eslint(no-debugger)")
+        .with_severity(Severity::Warning)
+        .with_help("Remove it")
+        .with_label(LabeledSpan::new_primary_with_span(
+          Some("here".into()),
+          0..9,
+        ))
+        .with_label(LabeledSpan::at(10..11, "")),
+    )
+    .with_fix(Some(Fix::from(TextEdit::delete(0..9))));
+
+    let cached = CachedDiagnostic::from(&diagnostic);
+    let json = serde_json::to_string(&cached).unwrap();
+    let back = Diagnostic::from(serde_json::from_str::<CachedDiagnostic>(&json).unwrap());
+
+    assert_eq!(back.inner, diagnostic.inner);
+    assert_eq!(back.fix, diagnostic.fix);
+  }
+
+  #[test]
+  fn should_miss_when_salt_or_path_changes() {
+    let dir = std::env::temp_dir().join(format!("doctor-cache-test-{}", std::process::id()));
+    let cache = ResultCache::new(&dir, "a");
+    cache.put("a.js", "x", &1).unwrap();
+
+    assert_eq!(cache.get::<i32>("a.js", "x"), Some(1));
+    assert_eq!(ResultCache::new(&dir, "b").get::<i32>("a.js", "x"), None);
+    assert_eq!(cache.get::<i32>("b.js", "x"), None);
+    assert!(dir.join(".gitignore").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn should_prune_entries_not_used_in_this_run() {
+    let dir = std::env::temp_dir().join(format!("doctor-cache-prune-{}", std::process::id()));
+    let before = ResultCache::new(&dir, "a");
+    before.put("a.js", "x", &1).unwrap();
+    before.put("b.js", "y", &2).unwrap();
+
+    let after = ResultCache::new(&dir, "a");
+    assert_eq!(after.get::<i32>("a.js", "x"), Some(1));
+    after.put("c.js", "z", &3).unwrap();
+    assert_eq!(after.prune().unwrap(), 1);
+
+    let next = ResultCache::new(&dir, "a");
+    assert_eq!(next.get::<i32>("a.js", "x"), Some(1));
+    assert_eq!(next.get::<i32>("b.js", "y"), None);
+    assert_eq!(next.get::<i32>("c.js", "z"), Some(3));
+    assert!(dir.join(".gitignore").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
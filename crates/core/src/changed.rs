@@ -0,0 +1,189 @@
+use std::{
+  collections::BTreeSet,
+  ops::Deref,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use crate::ValidatorError;
+
+/// Where the changed files come from, see [`ChangedFiles::from_git`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeSource {
+  /// Files changed since the merge base of a ref and `HEAD`, uncommitted changes included,
+  /// e.g. `origin/main`
+  ///
+  /// Like the diff of a pull request, commits that only landed on the ref do not count.
+  Since(String),
+  /// Files in the index
+  Staged,
+  /// Files git does not track yet, ignored files excluded
+  Untracked,
+}
+
+impl ChangeSource {
+  fn args(&self) -> Vec<&str> {
+    match self {
+      // 删除的文件没法检查，只要新增、复制、修改和重命名
+      Self::Since(r#ref) => vec![
+        "diff",
+        "--name-only",
+        "-z",
+        "--relative",
+        "--diff-filter=ACMR",
+        // 和 ref 的分叉点比较，ref 上后来的提交不算改动
+        "--merge-base",
+        // ref 来自用户输入，不能被当成 `--output=...` 之类的选项
+        "--end-of-options",
+        r#ref.as_str(),
+      ],
+      Self::Staged => vec![
+        "diff",
+        "--name-only",
+        "-z",
+        "--relative",
+        "--diff-filter=ACMR",
+        "--cached",
+      ],
+      Self::Untracked => vec!["ls-files", "-z", "--others", "--exclude-standard"],
+    }
+  }
+}
+
+/// An explicit set of files to check instead of the whole tree, absolute and deduplicated
+///
+/// # Examples
+///
+/// ```rust
+/// use std::path::PathBuf;
+///
+/// use doctor_core::ChangedFiles;
+///
+/// let files = ChangedFiles::new("/repo", ["src/a.js", "/repo/src/a.js", ".npmrc"]);
+///
+/// assert_eq!(files.len(), 2);
+/// assert!(files.contains("/repo/.npmrc"));
+/// assert!(!files.contains("/repo/src/b.js"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedFiles(BTreeSet<PathBuf>);
+
+impl ChangedFiles {
+  /// Relative paths are resolved against `cwd`
+  pub fn new<P: AsRef<Path>>(cwd: impl AsRef<Path>, paths: impl IntoIterator<Item = P>) -> Self {
+    let cwd = cwd.as_ref();
+    Self(paths.into_iter().map(|path| cwd.join(path)).collect())
+  }
+
+  /// Ask the local `git` CLI for the files of every source in `cwd`
+  ///
+  /// Only files under `cwd` are returned, deleted files are left out.
+  pub fn from_git(cwd: impl AsRef<Path>, sources: &[ChangeSource]) -> Result<Self, ValidatorError> {
+    let cwd = cwd.as_ref();
+    let mut files = BTreeSet::new();
+
+    for source in sources {
+      let output = Command::new("git")
+        .args(source.args())
+        .current_dir(cwd)
+        .output()?;
+
+      if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ValidatorError::Unknown(
+          format!(
+            "`git {}` failed: {}",
+            source.args().join(" "),
+            stderr.trim()
+          )
+          .into(),
+        ));
+      }
+
+      let stdout = String::from_utf8_lossy(&output.stdout);
+      files.extend(
+        stdout
+          .split('\0')
+          .filter(|path| !path.is_empty())
+          .map(|path| cwd.join(path)),
+      );
+    }
+
+    Ok(Self(files))
+  }
+
+  pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+    self.0.contains(path.as_ref())
+  }
+}
+
+impl Deref for ChangedFiles {
+  type Target = BTreeSet<PathBuf>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use super::*;
+  use crate::testing::project;
+
+  fn git(cwd: &Path, args: &[&str]) {
+    let status = Command::new("git")
+      .args(args)
+      .current_dir(cwd)
+      .status()
+      .unwrap();
+    assert!(status.success());
+  }
+
+  #[test]
+  fn should_collect_changed_files_from_git() {
+    let dir = project("changed", &[("a.js", "a"), ("b.js", "b")]);
+
+    git(&dir, &["init", "-q"]);
+    git(&dir, &["config", "user.email", "test@example.com"]);
+    git(&dir, &["config", "user.name", "test"]);
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "init"]);
+
+    fs::write(dir.join("a.js"), "a2").unwrap();
+    fs::write(dir.join("c.js"), "c").unwrap();
+    fs::write(dir.join("d.js"), "d").unwrap();
+    git(&dir, &["add", "c.js"]);
+
+    let staged = ChangedFiles::from_git(&dir, &[ChangeSource::Staged]).unwrap();
+    assert_eq!(staged, ChangedFiles::new(&dir, ["c.js"]));
+
+    let since = ChangedFiles::from_git(&dir, &[ChangeSource::Since("HEAD".to_string())]).unwrap();
+    assert_eq!(since, ChangedFiles::new(&dir, ["a.js", "c.js"]));
+
+    // main 在分叉后改了 b.js，不算当前分支的改动
+    git(&dir, &["branch", "main-tip"]);
+    git(&dir, &["stash", "-q"]);
+    git(&dir, &["checkout", "-q", "main-tip"]);
+    fs::write(dir.join("b.js"), "b2").unwrap();
+    git(&dir, &["commit", "-q", "-am", "main"]);
+    git(&dir, &["checkout", "-q", "-"]);
+    git(&dir, &["stash", "pop", "-q"]);
+    let since =
+      ChangedFiles::from_git(&dir, &[ChangeSource::Since("main-tip".to_string())]).unwrap();
+    assert_eq!(since, ChangedFiles::new(&dir, ["a.js", "c.js"]));
+
+    let untracked = ChangedFiles::from_git(&dir, &[ChangeSource::Untracked]).unwrap();
+    assert_eq!(untracked, ChangedFiles::new(&dir, ["d.js"]));
+
+    assert!(ChangedFiles::from_git(&dir, &[ChangeSource::Since("nope".to_string())]).is_err());
+
+    let output = dir.join("output");
+    let option = ChangeSource::Since(format!("--output={}", output.display()));
+    assert!(ChangedFiles::from_git(&dir, &[option]).is_err());
+    assert!(!output.exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
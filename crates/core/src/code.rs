@@ -0,0 +1,194 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+pub const SHINED_NAMESPACE: &str = "shined";
+
+pub const UNKNOWN: &str = "unknown";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid diagnostic code: {0}")]
+pub struct DiagnosticCodeParseError(pub String);
+
+/// A structured diagnostic code
+///
+/// Two textual styles are supported:
+///
+/// - doctor's own codes, `shined(package-json:missing-private)`
+/// - oxc codes, `eslint(no-debugger)`, which carry no validator part
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::DiagnosticCode;
+///
+/// let code: DiagnosticCode = "shined(npmrc:invalid-registry)".parse().unwrap();
+/// assert_eq!(code.namespace, "shined");
+/// assert_eq!(code.validator.as_deref(), Some("npmrc"));
+/// assert_eq!(code.rule, "invalid-registry");
+/// assert_eq!(code.to_string(), "shined(npmrc:invalid-registry)");
+///
+/// let code: DiagnosticCode = "eslint(no-debugger)".parse().unwrap();
+/// assert_eq!(code.validator, None);
+/// assert_eq!(code.to_string(), "eslint(no-debugger)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DiagnosticCode {
+  pub namespace: String,
+  pub validator: Option<String>,
+  pub rule: String,
+}
+
+impl DiagnosticCode {
+  pub fn new(
+    namespace: impl Into<String>,
+    validator: Option<String>,
+    rule: impl Into<String>,
+  ) -> Self {
+    Self {
+      namespace: namespace.into(),
+      validator,
+      rule: rule.into(),
+    }
+  }
+
+  /// Build a doctor code, e.g. `shined(npmrc:invalid-registry)`
+  pub fn shined(validator: impl Into<String>, rule: impl Into<String>) -> Self {
+    Self::new(SHINED_NAMESPACE, Some(validator.into()), rule)
+  }
+
+  /// The code used when a diagnostic does not carry one, `unknown(unknown)`
+  pub fn unknown() -> Self {
+    Self::new(UNKNOWN, None, UNKNOWN)
+  }
+
+  pub fn is_shined(&self) -> bool {
+    self.namespace == SHINED_NAMESPACE
+  }
+
+  pub fn is_unknown(&self) -> bool {
+    self.namespace == UNKNOWN
+  }
+
+  /// The validator part for doctor codes, the namespace (e.g. `eslint`) otherwise
+  pub fn group(&self) -> &str {
+    self.validator.as_deref().unwrap_or(&self.namespace)
+  }
+}
+
+impl fmt::Display for DiagnosticCode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.validator {
+      Some(validator) => write!(f, "{}({}:{})", self.namespace, validator, self.rule),
+      None => write!(f, "{}({})", self.namespace, self.rule),
+    }
+  }
+}
+
+impl FromStr for DiagnosticCode {
+  type Err = DiagnosticCodeParseError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || DiagnosticCodeParseError(s.to_string());
+
+    let (namespace, rest) = s.trim().split_once('(').ok_or_else(invalid)?;
+    let body = rest.strip_suffix(')').ok_or_else(invalid)?;
+
+    if namespace.is_empty() || body.is_empty() {
+      return Err(invalid());
+    }
+
+    let (validator, rule) = match body.split_once(':') {
+      Some((validator, rule)) if !validator.is_empty() && !rule.is_empty() => {
+        (Some(validator.to_string()), rule)
+      }
+      Some(_) => return Err(invalid()),
+      None => (None, body),
+    };
+
+    Ok(Self::new(namespace, validator, rule))
+  }
+}
+
+impl From<DiagnosticCode> for String {
+  fn from(code: DiagnosticCode) -> Self {
+    code.to_string()
+  }
+}
+
+impl Serialize for DiagnosticCode {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for DiagnosticCode {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_parse_shined_code() {
+    let code = "shined(package-json:missing-private)"
+      .parse::<DiagnosticCode>()
+      .unwrap();
+    assert_eq!(
+      code,
+      DiagnosticCode::shined("package-json", "missing-private")
+    );
+    assert!(code.is_shined());
+    assert_eq!(code.group(), "package-json");
+  }
+
+  #[test]
+  fn should_parse_oxc_code() {
+    let code = "eslint-plugin-react(jsx-no-undef)"
+      .parse::<DiagnosticCode>()
+      .unwrap();
+    assert_eq!(code.namespace, "eslint-plugin-react");
+    assert_eq!(code.validator, None);
+    assert_eq!(code.rule, "jsx-no-undef");
+    assert_eq!(code.group(), "eslint-plugin-react");
+  }
+
+  #[test]
+  fn should_round_trip_display() {
+    for raw in [
+      "shined(node-version:invalid-version-range)",
+      "typescript-eslint(no-misused-new)",
+      "unknown(unknown)",
+    ] {
+      let code = raw.parse::<DiagnosticCode>().unwrap();
+      assert_eq!(code.to_string(), raw);
+    }
+  }
+
+  #[test]
+  fn should_reject_malformed_code() {
+    for raw in [
+      "",
+      "shined",
+      "shined()",
+      "(rule)",
+      "shined(:rule)",
+      "shined(npmrc:)",
+    ] {
+      assert!(raw.parse::<DiagnosticCode>().is_err(), "{raw}");
+    }
+  }
+
+  #[test]
+  fn should_serialize_as_string() {
+    let code = DiagnosticCode::shined("npmrc", "missing-registry");
+    let json = serde_json::to_string(&code).unwrap();
+    assert_eq!(json, r#""shined(npmrc:missing-registry)""#);
+    let back: DiagnosticCode = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, code);
+  }
+}
@@ -0,0 +1,87 @@
+use std::error::Error;
+
+use miette::{MietteDiagnostic, Severity};
+
+use crate::{DOCTOR_VALIDATOR, Diagnostic, DiagnosticCode, Messages};
+
+/// Turn a failed validator run into a `shined(doctor:validator-crashed)` diagnostic
+///
+/// The messages are tagged with the `doctor` validator rather than the crashed one, so a
+/// baseline does not take the missing results as fixed.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::validator_crashed;
+///
+/// let error = std::io::Error::other("permission denied");
+/// let messages = validator_crashed("npmrc", &error);
+///
+/// assert_eq!(messages[0].code.as_deref(), Some("shined(doctor:validator-crashed)"));
+/// assert!(messages[0].message.contains("permission denied"));
+/// ```
+pub fn validator_crashed(validator: &str, error: &(dyn Error + 'static)) -> Messages {
+  let mut causes = Vec::new();
+  let mut source = error.source();
+  while let Some(cause) = source {
+    causes.push(format!("Caused by: {}", cause));
+    source = cause.source();
+  }
+
+  let mut diagnostic =
+    MietteDiagnostic::new(format!("Validator `{}` crashed: {}", validator, error))
+      .with_code(DiagnosticCode::shined(
+        DOCTOR_VALIDATOR,
+        "validator-crashed",
+      ))
+      .with_severity(Severity::Error);
+
+  // 没有 cause 时 help 留空，避免重复 message
+  if !causes.is_empty() {
+    diagnostic = diagnostic.with_help(causes.join("\n"));
+  }
+
+  Messages::builder()
+    .diagnostics(vec![Diagnostic::from(diagnostic)])
+    .validator(DOCTOR_VALIDATOR)
+    .build()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{fmt, io};
+
+  use super::*;
+
+  #[derive(Debug)]
+  struct ParseError(io::Error);
+
+  impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      write!(f, "failed to parse .node-version")
+    }
+  }
+
+  impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+      Some(&self.0)
+    }
+  }
+
+  #[test]
+  fn should_keep_error_chain_in_help() {
+    let error = ParseError(io::Error::other("unexpected character"));
+
+    let messages = validator_crashed("node-version", &error);
+
+    assert_eq!(messages.validator.as_deref(), Some(DOCTOR_VALIDATOR));
+    assert_eq!(
+      messages[0].message,
+      "Validator `node-version` crashed: failed to parse .node-version"
+    );
+    assert_eq!(
+      messages[0].help.as_deref(),
+      Some("Caused by: unexpected character")
+    );
+  }
+}
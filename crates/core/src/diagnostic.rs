@@ -1,13 +1,46 @@
 use std::ops::{Deref, DerefMut};
 
-use miette::MietteDiagnostic;
+use miette::{LabeledSpan, MietteDiagnostic};
 use oxc::diagnostics::{OxcDiagnostic, Severity};
 
-pub struct Diagnostic(pub MietteDiagnostic);
+use crate::{
+  DiagnosticCode, Fix, UNKNOWN,
+  loc::{LineIndex, Location},
+};
+
+/// A [`MietteDiagnostic`] with an optional machine-applicable [`Fix`]
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+  pub inner: MietteDiagnostic,
+  pub fix: Option<Fix>,
+}
+
+impl Diagnostic {
+  pub fn with_fix(mut self, fix: impl Into<Option<Fix>>) -> Self {
+    self.fix = fix.into();
+    self
+  }
+
+  /// The label marked as primary, the first label when none is
+  pub fn primary_label(&self) -> Option<&LabeledSpan> {
+    let labels = self.labels.as_ref()?;
+    labels
+      .iter()
+      .find(|label| label.primary())
+      .or_else(|| labels.first())
+  }
+
+  /// The location of [`Diagnostic::primary_label`], `None` when there are no labels
+  pub fn primary_location(&self, index: &LineIndex) -> Option<Location> {
+    self
+      .primary_label()
+      .map(|label| index.location(label.offset(), label.len()))
+  }
+}
 
 impl Into<MietteDiagnostic> for Diagnostic {
   fn into(self) -> MietteDiagnostic {
-    self.0
+    self.inner
   }
 }
 
@@ -15,19 +48,22 @@ impl Deref for Diagnostic {
   type Target = MietteDiagnostic;
 
   fn deref(&self) -> &Self::Target {
-    &self.0
+    &self.inner
   }
 }
 
 impl DerefMut for Diagnostic {
   fn deref_mut(&mut self) -> &mut Self::Target {
-    &mut self.0
+    &mut self.inner
   }
 }
 
 impl From<MietteDiagnostic> for Diagnostic {
   fn from(value: MietteDiagnostic) -> Self {
-    Diagnostic(value)
+    Diagnostic {
+      inner: value,
+      fix: None,
+    }
   }
 }
 
@@ -39,19 +75,19 @@ impl From<OxcDiagnostic> for Diagnostic {
       diagnostic = diagnostic.with_help(help.to_string());
     }
 
-    let code = oxc_diagnostics
+    let namespace = oxc_diagnostics
       .code
       .scope
       .as_ref()
-      .map_or(String::from("unknown"), |s| s.to_string());
+      .map_or(String::from(UNKNOWN), |s| s.to_string());
 
-    let number = oxc_diagnostics
+    let rule = oxc_diagnostics
       .code
       .number
       .as_ref()
-      .map_or(String::from("unknown"), |s| s.to_string());
+      .map_or(String::from(UNKNOWN), |s| s.to_string());
 
-    diagnostic = diagnostic.with_code(format!("{code}({number})"));
+    diagnostic = diagnostic.with_code(DiagnosticCode::new(namespace, None, rule));
 
     match oxc_diagnostics.severity {
       Severity::Error => diagnostic = diagnostic.with_severity(miette::Severity::Error),
@@ -78,6 +114,6 @@ impl From<OxcDiagnostic> for Diagnostic {
       diagnostic = diagnostic.with_url(url.to_string());
     }
 
-    Diagnostic(diagnostic)
+    Diagnostic::from(diagnostic)
   }
 }
@@ -0,0 +1,118 @@
+use sha2::{Digest, Sha256};
+
+use crate::{Diagnostic, traits::DiagnosticCodeExt};
+
+/// A stable identifier of `diagnostic` in the file at `path`
+///
+/// The code, `path` and the whitespace-normalized text of the primary label are hashed, line
+/// numbers are not, so the fingerprint survives edits elsewhere in the file. Diagnostics
+/// without labels use their message instead of a snippet.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::{Diagnostic, fingerprint};
+/// use miette::{LabeledSpan, MietteDiagnostic};
+///
+/// let diagnostic = |offset: usize| {
+///   Diagnostic::from(
+///     MietteDiagnostic::new("`debugger` statement is not allowed")
+///       .with_code("eslint(no-debugger)")
+///       .with_label(LabeledSpan::at(offset..offset + 9, "")),
+///   )
+/// };
+///
+/// let before = fingerprint("src/a.js", "debugger;", &diagnostic(0));
+/// let after = fingerprint("src/a.js", "\n\n  debugger;", &diagnostic(4));
+///
+/// assert_eq!(before, after);
+/// ```
+pub fn fingerprint(path: &str, source_code: &str, diagnostic: &Diagnostic) -> String {
+  let snippet = diagnostic
+    .primary_label()
+    .and_then(|label| source_code.get(label.offset()..label.offset() + label.len()))
+    .unwrap_or(&diagnostic.message);
+
+  fingerprint_of(
+    &diagnostic.diagnostic_code_or_unknown().to_string(),
+    path,
+    snippet,
+  )
+}
+
+/// The hash behind [`fingerprint`], for callers that pick the snippet themselves
+pub fn fingerprint_of(code: &str, path: &str, snippet: &str) -> String {
+  let snippet = snippet.split_whitespace().collect::<Vec<_>>().join(" ");
+
+  let mut hasher = Sha256::new();
+  hasher.update(code);
+  hasher.update([0]);
+  hasher.update(path.replace('\\', "/"));
+  hasher.update([0]);
+  hasher.update(snippet);
+
+  format!("{:x}", hasher.finalize())
+}
+
+/// Tell apart diagnostics of one file that share a [`fingerprint`], e.g. the same call flagged
+/// twice
+///
+/// `occurrence` counts them from 0 in report order, the first one keeps `fingerprint` as is.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::fingerprint_occurrence;
+///
+/// assert_eq!(fingerprint_occurrence("abc", 0), "abc");
+/// assert_ne!(fingerprint_occurrence("abc", 1), "abc");
+/// assert_ne!(fingerprint_occurrence("abc", 1), fingerprint_occurrence("abc", 2));
+/// ```
+pub fn fingerprint_occurrence(fingerprint: &str, occurrence: usize) -> String {
+  if occurrence == 0 {
+    return fingerprint.to_string();
+  }
+
+  let mut hasher = Sha256::new();
+  hasher.update(fingerprint);
+  hasher.update([0]);
+  hasher.update(occurrence.to_string());
+
+  format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+  use miette::{LabeledSpan, MietteDiagnostic};
+
+  use super::*;
+
+  fn diagnostic(code: &str, span: std::ops::Range<usize>) -> Diagnostic {
+    Diagnostic::from(
+      MietteDiagnostic::new("message")
+        .with_code(code)
+        .with_label(LabeledSpan::at(span, "")),
+    )
+  }
+
+  #[test]
+  fn should_ignore_whitespace_changes_in_snippet() {
+    let a = fingerprint("a.js", "foo( 1,\n  2 )", &diagnostic("eslint(x)", 0..13));
+    let b = fingerprint("a.js", "foo( 1, 2 )", &diagnostic("eslint(x)", 0..11));
+    assert_eq!(a, b);
+    assert_eq!(a.len(), 64);
+  }
+
+  #[test]
+  fn should_differ_by_code_and_path() {
+    let base = fingerprint("a.js", "foo", &diagnostic("eslint(x)", 0..3));
+    assert_ne!(
+      base,
+      fingerprint("b.js", "foo", &diagnostic("eslint(x)", 0..3))
+    );
+    assert_ne!(
+      base,
+      fingerprint("a.js", "foo", &diagnostic("eslint(y)", 0..3))
+    );
+  }
+}
@@ -0,0 +1,206 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+/// A single replacement of the byte range `start..end` with `content`
+///
+/// Insertions are edits with `start == end`, deletions are edits with an empty `content`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TextEdit {
+  pub start: usize,
+  pub end: usize,
+  pub content: String,
+}
+
+impl TextEdit {
+  pub fn replace(range: Range<usize>, content: impl Into<String>) -> Self {
+    Self {
+      start: range.start,
+      end: range.end,
+      content: content.into(),
+    }
+  }
+
+  pub fn insert(offset: usize, content: impl Into<String>) -> Self {
+    Self::replace(offset..offset, content)
+  }
+
+  pub fn delete(range: Range<usize>) -> Self {
+    Self::replace(range, String::new())
+  }
+
+  fn overlaps(&self, other: &TextEdit) -> bool {
+    if self.start == self.end && other.start == other.end {
+      // two insertions at the same offset have no well defined order
+      return self.start == other.start;
+    }
+    self.start < other.end && other.start < self.end
+  }
+
+  fn is_valid_for(&self, source: &str) -> bool {
+    self.start <= self.end
+      && self.end <= source.len()
+      && source.is_char_boundary(self.start)
+      && source.is_char_boundary(self.end)
+  }
+}
+
+/// A machine-applicable fix, all of its edits are applied together or not at all
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Fix {
+  pub message: Option<String>,
+  pub edits: Vec<TextEdit>,
+}
+
+impl Fix {
+  pub fn new(edits: Vec<TextEdit>) -> Self {
+    Self {
+      message: None,
+      edits,
+    }
+  }
+
+  pub fn with_message(mut self, message: impl Into<String>) -> Self {
+    self.message = Some(message.into());
+    self
+  }
+}
+
+impl From<TextEdit> for Fix {
+  fn from(edit: TextEdit) -> Self {
+    Self::new(vec![edit])
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixResult {
+  pub output: String,
+  pub applied: usize,
+  pub skipped: usize,
+}
+
+impl FixResult {
+  pub fn is_changed(&self) -> bool {
+    self.applied > 0
+  }
+}
+
+/// Apply `fixes` to `source`
+///
+/// Fixes are taken in order of their first edit, a fix that overlaps one already taken or
+/// points outside of `source` is skipped as a whole.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::{Fix, TextEdit, apply_fixes};
+///
+/// let fixes = vec![
+///   Fix::from(TextEdit::replace(0..3, "let")),
+///   Fix::from(TextEdit::insert(9, ";")),
+/// ];
+///
+/// let result = apply_fixes("var a = 1", &fixes);
+///
+/// assert_eq!(result.output, "let a = 1;");
+/// assert_eq!(result.applied, 2);
+/// ```
+pub fn apply_fixes<'a>(source: &str, fixes: impl IntoIterator<Item = &'a Fix>) -> FixResult {
+  let mut fixes = fixes
+    .into_iter()
+    .filter(|fix| !fix.edits.is_empty())
+    .collect::<Vec<_>>();
+
+  fixes.sort_by_key(|fix| fix.edits.iter().map(|edit| edit.start).min());
+
+  let mut accepted: Vec<&TextEdit> = Vec::new();
+  let mut applied = 0;
+  let mut skipped = 0;
+
+  for fix in fixes {
+    let conflicts = fix.edits.iter().enumerate().any(|(index, edit)| {
+      !edit.is_valid_for(source)
+        || accepted.iter().any(|other| edit.overlaps(other))
+        || fix.edits[..index].iter().any(|other| edit.overlaps(other))
+    });
+
+    if conflicts {
+      skipped += 1;
+      continue;
+    }
+
+    accepted.extend(fix.edits.iter());
+    applied += 1;
+  }
+
+  // 从后往前应用，避免位置偏移
+  accepted.sort_by(|a, b| b.start.cmp(&a.start).then(b.end.cmp(&a.end)));
+
+  let mut output = source.to_string();
+  for edit in accepted {
+    output.replace_range(edit.start..edit.end, &edit.content);
+  }
+
+  FixResult {
+    output,
+    applied,
+    skipped,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_apply_non_overlapping_fixes() {
+    let fixes = vec![
+      Fix::from(TextEdit::insert(0, "\"use strict\";\n")),
+      Fix::from(TextEdit::replace(4..5, "b")),
+      Fix::from(TextEdit::delete(9..10)),
+    ];
+
+    let result = apply_fixes("let a = 1;", &fixes);
+
+    assert_eq!(result.output, "\"use strict\";\nlet b = 1");
+    assert_eq!(result.applied, 3);
+    assert_eq!(result.skipped, 0);
+  }
+
+  #[test]
+  fn should_skip_overlapping_fix_as_a_whole() {
+    let fixes = vec![
+      Fix::from(TextEdit::replace(0..5, "hello")),
+      Fix::new(vec![
+        TextEdit::replace(3..7, "xx"),
+        TextEdit::insert(10, "!"),
+      ]),
+    ];
+
+    let result = apply_fixes("abcdefghij", &fixes);
+
+    assert_eq!(result.output, "hellofghij");
+    assert_eq!(result.applied, 1);
+    assert_eq!(result.skipped, 1);
+  }
+
+  #[test]
+  fn should_skip_out_of_bounds_fix() {
+    let fixes = vec![Fix::from(TextEdit::replace(2..20, ""))];
+
+    let result = apply_fixes("abc", &fixes);
+
+    assert_eq!(result.output, "abc");
+    assert!(!result.is_changed());
+  }
+
+  #[test]
+  fn should_skip_fix_inside_multibyte_char() {
+    let fixes = vec![Fix::from(TextEdit::insert(1, "x"))];
+
+    let result = apply_fixes("你好", &fixes);
+
+    assert_eq!(result.output, "你好");
+    assert_eq!(result.skipped, 1);
+  }
+}
@@ -1,5 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub const IGNORE_PATTERNS: [&str; 17] = [
@@ -22,7 +23,7 @@ pub const IGNORE_PATTERNS: [&str; 17] = [
   "**/build/**",
 ];
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
 pub struct Ignore(pub Vec<String>);
 
 impl Default for Ignore {
@@ -1,13 +1,44 @@
+mod baseline;
+mod cache;
+mod changed;
+mod code;
+mod crash;
 mod diagnostic;
 mod error;
+mod fingerprint;
+mod fix;
 mod hack_source_type;
 mod ignore;
 pub mod loc;
 mod message;
+mod patch;
+mod policy;
+mod registry;
+mod report;
+mod severity;
+mod suppression;
+mod workspace;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 pub mod traits;
+pub use baseline::*;
+pub use cache::*;
+pub use changed::*;
+pub use code::*;
+pub use crash::*;
 pub use diagnostic::*;
 pub use error::*;
+pub use fingerprint::*;
+pub use fix::*;
 pub use hack_source_type::*;
 pub use ignore::*;
 pub use message::*;
+pub use patch::*;
+pub use policy::*;
+pub use registry::*;
+pub use report::*;
+pub use severity::*;
+pub use suppression::*;
+pub use workspace::*;
@@ -0,0 +1,180 @@
+use std::ops::Range;
+
+use super::{Location, Position};
+
+/// Line starts of a source text, built once and queried in O(log n)
+///
+/// `\n`, `\r\n` and a lone `\r` end a line. Offsets past the end of the source are clamped
+/// to it, offsets inside a multi-byte char are moved back to the start of that char.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::loc::{ColumnEncoding, LineIndex};
+///
+/// let index = LineIndex::new("let a = 1;\n// 😀 你好\n");
+///
+/// let position = index.position(22);
+///
+/// assert_eq!(position.row, 2);
+/// assert_eq!(position.column(ColumnEncoding::Byte), 12);
+/// assert_eq!(position.column(ColumnEncoding::Char), 7);
+/// assert_eq!(position.column(ColumnEncoding::Utf16), 8);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+  source: &'a str,
+  line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+  pub fn new(source: &'a str) -> Self {
+    let bytes = source.as_bytes();
+    let mut line_starts = vec![0];
+
+    let mut i = 0;
+    while i < bytes.len() {
+      match bytes[i] {
+        b'\n' => line_starts.push(i + 1),
+        b'\r' => {
+          if bytes.get(i + 1) == Some(&b'\n') {
+            i += 1;
+          }
+          line_starts.push(i + 1);
+        }
+        _ => {}
+      }
+      i += 1;
+    }
+
+    Self {
+      source,
+      line_starts,
+    }
+  }
+
+  pub fn source(&self) -> &'a str {
+    self.source
+  }
+
+  pub fn line_count(&self) -> usize {
+    self.line_starts.len()
+  }
+
+  /// Byte ranges of every line, without the line break
+  pub fn lines(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+    self.line_starts.iter().enumerate().map(|(i, &start)| {
+      let end = self
+        .line_starts
+        .get(i + 1)
+        .copied()
+        .unwrap_or(self.source.len());
+      start..start + self.source[start..end].trim_end_matches(['\r', '\n']).len()
+    })
+  }
+
+  fn clamp(&self, offset: usize) -> usize {
+    let mut offset = offset.min(self.source.len());
+    while !self.source.is_char_boundary(offset) {
+      offset -= 1;
+    }
+    offset
+  }
+
+  /// The 1-based position of the byte `offset`
+  pub fn position(&self, offset: usize) -> Position {
+    let offset = self.clamp(offset);
+
+    // line_starts[0] == 0, so there is always at least one start <= offset
+    let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+    let line_start = self.line_starts[line];
+
+    let mut col = 0;
+    let mut utf16_col = 0;
+    for ch in self.source[line_start..offset].chars() {
+      col += 1;
+      utf16_col += ch.len_utf16();
+    }
+
+    Position {
+      row: line + 1,
+      col: col + 1,
+      byte_col: offset - line_start + 1,
+      utf16_col: utf16_col + 1,
+    }
+  }
+
+  /// The location of the byte range `offset..offset + len`
+  pub fn location(&self, offset: usize, len: usize) -> Location {
+    Location {
+      start: self.position(offset),
+      end: self.position(offset + len),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn row_col(position: Position) -> (usize, usize) {
+    (position.row, position.col)
+  }
+
+  #[test]
+  fn should_handle_all_line_endings() {
+    let index = LineIndex::new("a\nb\r\nc\rd");
+
+    assert_eq!(index.line_count(), 4);
+    assert_eq!(row_col(index.position(2)), (2, 1));
+    assert_eq!(row_col(index.position(5)), (3, 1));
+    assert_eq!(row_col(index.position(7)), (4, 1));
+  }
+
+  #[test]
+  fn should_split_lines_without_breaks() {
+    let source = "a\nb\r\nc\rd";
+    let index = LineIndex::new(source);
+
+    let lines = index
+      .lines()
+      .map(|range| &source[range])
+      .collect::<Vec<_>>();
+
+    assert_eq!(lines, vec!["a", "b", "c", "d"]);
+  }
+
+  #[test]
+  fn should_clamp_out_of_range_offsets() {
+    let index = LineIndex::new("ab\ncd");
+
+    assert_eq!(row_col(index.position(100)), (2, 3));
+  }
+
+  #[test]
+  fn should_move_offset_inside_char_to_its_start() {
+    let index = LineIndex::new("你好");
+
+    assert_eq!(row_col(index.position(4)), (1, 2));
+  }
+
+  #[test]
+  fn should_count_columns_in_every_encoding() {
+    // 😀 是 4 字节、2 个 UTF-16 code unit
+    let index = LineIndex::new("a😀b");
+
+    let position = index.position(5);
+
+    assert_eq!(position.byte_col, 6);
+    assert_eq!(position.col, 3);
+    assert_eq!(position.utf16_col, 4);
+  }
+
+  #[test]
+  fn should_point_end_of_line_before_break() {
+    let index = LineIndex::new("ab\ncd\n");
+
+    assert_eq!(row_col(index.location(0, 2).end), (1, 3));
+    assert_eq!(row_col(index.position(6)), (3, 1));
+  }
+}
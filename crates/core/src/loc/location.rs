@@ -1,22 +1,16 @@
-use super::Position;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use super::{LineIndex, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Location {
   pub start: Position,
   pub end: Position,
 }
 
 impl Location {
-  pub fn new(source_code: String, offset: usize, len: usize) -> Self {
-    let start = offset;
-    let end = offset + len;
-
-    let start_pos = Position::from_source(&source_code, start);
-    let end_pos = Position::from_source(&source_code, end);
-
-    Self {
-      start: start_pos,
-      end: end_pos,
-    }
+  /// Builds a [`LineIndex`] for a single query, use the index directly for many ranges
+  pub fn new(source_code: &str, offset: usize, len: usize) -> Self {
+    LineIndex::new(source_code).location(offset, len)
   }
 }
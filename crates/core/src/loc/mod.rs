@@ -1,9 +1,11 @@
+mod line_index;
 mod location;
 mod position;
 
+pub use line_index::*;
 pub use location::*;
 pub use position::*;
 
-pub fn get_source_location(source_code: String, offset: usize, len: usize) -> Location {
+pub fn get_source_location(source_code: &str, offset: usize, len: usize) -> Location {
   Location::new(source_code, offset, len)
 }
@@ -1,21 +1,42 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use serde::{Deserialize, Serialize};
+
+use super::LineIndex;
+
+/// How a column is counted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnEncoding {
+  /// UTF-8 bytes
+  Byte,
+  /// Unicode scalar values
+  #[default]
+  Char,
+  /// UTF-16 code units, as counted by editors and JavaScript strings
+  Utf16,
+}
+
+/// A 1-based position, the column is available in every [`ColumnEncoding`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Position {
   pub row: usize,
+  /// The column in chars
   pub col: usize,
+  pub byte_col: usize,
+  pub utf16_col: usize,
 }
 
 impl Position {
+  /// Builds a [`LineIndex`] for a single query, use the index directly for many offsets
   pub fn from_source(source_text: &str, offset: usize) -> Self {
-    let rope = ropey::Rope::from_str(&source_text);
-    let line = rope.try_byte_to_line(offset).unwrap_or(0);
-
-    let first_char_of_line = rope.try_line_to_char(line).unwrap_or(0);
-    let offset = rope.try_byte_to_char(offset).unwrap_or(0);
-    let col = offset - first_char_of_line;
+    LineIndex::new(source_text).position(offset)
+  }
 
-    Self {
-      row: line + 1,
-      col: col + 1,
+  pub fn column(&self, encoding: ColumnEncoding) -> usize {
+    match encoding {
+      ColumnEncoding::Byte => self.byte_col,
+      ColumnEncoding::Char => self.col,
+      ColumnEncoding::Utf16 => self.utf16_col,
     }
   }
 }
@@ -1,8 +1,12 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+  ops::{Deref, DerefMut},
+  path::Path,
+};
 
-use miette::MietteDiagnostic;
 use typed_builder::TypedBuilder;
 
+use crate::{Diagnostic, Fix, FixResult, apply_fixes, loc::LineIndex, traits::DiagnosticCodeExt};
+
 #[derive(TypedBuilder, Clone, Debug)]
 pub struct Messages {
   #[builder(default = String::new())]
@@ -10,11 +14,17 @@ pub struct Messages {
   #[builder(default = String::new())]
   pub source_path: String,
   #[builder(default = Vec::new())]
-  pub diagnostics: Vec<MietteDiagnostic>,
+  pub diagnostics: Vec<Diagnostic>,
+  /// The validator that produced these messages, e.g. `npmrc`
+  #[builder(default, setter(strip_option, into))]
+  pub validator: Option<String>,
+  /// The workspace package `source_path` belongs to, `None` for the root, see [`crate::Workspace`]
+  #[builder(default, setter(strip_option, into))]
+  pub package: Option<String>,
 }
 
 impl Deref for Messages {
-  type Target = Vec<MietteDiagnostic>;
+  type Target = Vec<Diagnostic>;
 
   fn deref(&self) -> &Self::Target {
     &self.diagnostics
@@ -33,7 +43,7 @@ impl Messages {
 
     for diagnostic in &self.diagnostics {
       let source = miette::NamedSource::new(self.source_path.clone(), self.source_code.clone());
-      let report = miette::Report::new(diagnostic.to_owned()).with_source_code(source);
+      let report = miette::Report::new(diagnostic.inner.to_owned()).with_source_code(source);
       let report_str = format!("{:?}", report);
       reports.push(report_str.clone());
       eprintln!("{}", report_str);
@@ -46,12 +56,67 @@ impl Messages {
     let mut reports = Vec::new();
     for diagnostic in &self.diagnostics {
       let source = miette::NamedSource::new(self.source_path.clone(), self.source_code.clone());
-      let report = miette::Report::new(diagnostic.to_owned()).with_source_code(source);
+      let report = miette::Report::new(diagnostic.inner.to_owned()).with_source_code(source);
       reports.push(report);
     }
     return reports;
   }
 
+  /// The validator `diagnostic` belongs to, the code group is used when these messages are not tagged
+  pub fn validator_of(&self, diagnostic: &Diagnostic) -> String {
+    match &self.validator {
+      Some(validator) => validator.clone(),
+      None => diagnostic.diagnostic_code_or_unknown().group().to_string(),
+    }
+  }
+
+  /// `source_path` relative to `root` with `/` separators, unchanged when outside of `root`
+  pub fn relative_path(&self, root: &Path) -> String {
+    let path = Path::new(&self.source_path);
+    path
+      .strip_prefix(root)
+      .unwrap_or(path)
+      .to_string_lossy()
+      .replace('\\', "/")
+  }
+
+  /// Build a [`LineIndex`] of `source_code`, build it once and reuse it for all diagnostics
+  pub fn line_index(&self) -> LineIndex<'_> {
+    LineIndex::new(&self.source_code)
+  }
+
+  pub fn fixes(&self) -> impl Iterator<Item = &Fix> {
+    self.diagnostics.iter().filter_map(|d| d.fix.as_ref())
+  }
+
+  pub fn has_fix(&self) -> bool {
+    self.fixes().next().is_some()
+  }
+
+  /// Apply the fixes of all diagnostics to `source_code`
+  pub fn apply_fixes(&self) -> FixResult {
+    apply_fixes(&self.source_code, self.fixes())
+  }
+
+  /// Apply the fixes of all diagnostics and write the result back to `source_path`
+  ///
+  /// Returns `false` when nothing changed.
+  pub fn write_fixes(&self) -> std::io::Result<bool> {
+    if self.source_path.is_empty() {
+      return Ok(false);
+    }
+
+    let result = self.apply_fixes();
+
+    if !result.is_changed() || result.output == self.source_code {
+      return Ok(false);
+    }
+
+    std::fs::write(&self.source_path, result.output)?;
+
+    Ok(true)
+  }
+
   pub fn has_error(&self) -> bool {
     self.diagnostics.iter().any(|d| {
       if let Some(severity) = d.severity {
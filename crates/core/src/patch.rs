@@ -0,0 +1,202 @@
+use std::{
+  fmt,
+  ops::Deref,
+  path::{Path, PathBuf},
+};
+
+use similar::TextDiff;
+
+use crate::Messages;
+
+const DEV_NULL: &str = "/dev/null";
+
+/// The pending changes of a single file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+  pub path: PathBuf,
+  pub original: String,
+  pub fixed: String,
+  /// The file does not exist yet, the diff is emitted against `/dev/null`
+  pub is_new: bool,
+}
+
+impl FilePatch {
+  /// Build the patch of `messages`, `None` when its fixes change nothing
+  pub fn from_messages(messages: &Messages) -> Option<Self> {
+    if messages.source_path.is_empty() {
+      return None;
+    }
+
+    let result = messages.apply_fixes();
+
+    if !result.is_changed() || result.output == messages.source_code {
+      return None;
+    }
+
+    let path = PathBuf::from(&messages.source_path);
+    let is_new = messages.source_code.is_empty() && !path.exists();
+
+    Some(Self {
+      path,
+      original: messages.source_code.clone(),
+      fixed: result.output,
+      is_new,
+    })
+  }
+
+  /// Render the patch as a unified diff with `a/` and `b/` prefixed paths,
+  /// which is what `git apply` expects by default
+  pub fn unified_diff(&self) -> String {
+    let path = self.path.to_string_lossy().replace('\\', "/");
+
+    let old_header = if self.is_new {
+      DEV_NULL.to_string()
+    } else {
+      format!("a/{}", path)
+    };
+    let new_header = format!("b/{}", path);
+
+    let diff = TextDiff::from_lines(&self.original, &self.fixed);
+
+    diff
+      .unified_diff()
+      .context_radius(3)
+      .header(&old_header, &new_header)
+      .to_string()
+  }
+
+  /// Rewrite `path` relative to `root`, paths outside of `root` are left untouched
+  pub fn strip_prefix(mut self, root: &Path) -> Self {
+    if let Ok(relative) = self.path.strip_prefix(root) {
+      self.path = relative.to_path_buf();
+    }
+    self
+  }
+}
+
+/// An aggregate of [`FilePatch`], in the order the files were fixed
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::{Diagnostic, Fix, Messages, Patch, TextEdit};
+///
+/// let diagnostic = Diagnostic::from(miette::MietteDiagnostic::new("use let"))
+///   .with_fix(Fix::from(TextEdit::replace(0..3, "let")));
+///
+/// let messages = Messages::builder()
+///   .source_code("var a = 1;\n".to_string())
+///   .source_path("src/index.js".to_string())
+///   .diagnostics(vec![diagnostic])
+///   .build();
+///
+/// let patch = Patch::from_messages(&[messages]);
+///
+/// assert_eq!(patch.len(), 1);
+/// assert!(patch.to_string().contains("+let a = 1;"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Patch(pub Vec<FilePatch>);
+
+impl Deref for Patch {
+  type Target = Vec<FilePatch>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl Patch {
+  pub fn from_messages(messages: &[Messages]) -> Self {
+    Self(
+      messages
+        .iter()
+        .filter_map(FilePatch::from_messages)
+        .collect(),
+    )
+  }
+
+  /// See [`FilePatch::strip_prefix`]
+  pub fn strip_prefix(self, root: &Path) -> Self {
+    Self(
+      self
+        .0
+        .into_iter()
+        .map(|file| file.strip_prefix(root))
+        .collect(),
+    )
+  }
+}
+
+impl fmt::Display for Patch {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for file in &self.0 {
+      f.write_str(&file.unified_diff())?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Diagnostic, Fix, TextEdit, testing::messages};
+
+  fn fixable(fixes: Vec<Fix>) -> Vec<Diagnostic> {
+    fixes
+      .into_iter()
+      .map(|fix| Diagnostic::from(miette::MietteDiagnostic::new("fixable")).with_fix(fix))
+      .collect()
+  }
+
+  #[test]
+  fn should_render_git_style_unified_diff() {
+    let messages = messages(
+      "lint",
+      "/repo/src/index.js",
+      "var a = 1;\nconsole.log(a);\n",
+      fixable(vec![Fix::from(TextEdit::replace(0..3, "let"))]),
+    );
+
+    let patch = Patch::from_messages(&[messages]).strip_prefix(Path::new("/repo"));
+
+    assert_eq!(
+      patch.to_string(),
+      "--- a/src/index.js\n+++ b/src/index.js\n@@ -1,2 +1,2 @@\n-var a = 1;\n+let a = 1;\n console.log(a);\n"
+    );
+  }
+
+  #[test]
+  fn should_diff_new_file_against_dev_null() {
+    let messages = messages(
+      "lint",
+      "/definitely/not/here/.npmrc",
+      "",
+      fixable(vec![Fix::from(TextEdit::insert(
+        0,
+        "registry=https://r.example/\n",
+      ))]),
+    );
+
+    let patch = Patch::from_messages(&[messages]);
+
+    assert_eq!(patch.len(), 1);
+    assert!(patch[0].is_new);
+    assert!(patch.to_string().starts_with("--- /dev/null\n"));
+  }
+
+  #[test]
+  fn should_skip_files_without_changes() {
+    let unchanged = messages("lint", "/repo/a.js", "let a = 1;\n", vec![]);
+    let noop = messages(
+      "lint",
+      "/repo/b.js",
+      "let a = 1;\n",
+      fixable(vec![Fix::from(TextEdit::replace(0..3, "let"))]),
+    );
+
+    let patch = Patch::from_messages(&[unchanged, noop]);
+
+    assert!(patch.is_empty());
+  }
+}
@@ -0,0 +1,129 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// What `package.json` must say about `private`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivatePolicy {
+  /// Not checked
+  Off,
+  /// Must be set, to anything
+  Exist,
+  True,
+  False,
+}
+
+/// Which `package.json` rules run, an unset field keeps the built-in default
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct PackageJsonPolicy {
+  /// `name` must be set
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub name: Option<bool>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub private: Option<PrivatePolicy>,
+  /// `packageManager` must be set
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub package_manager: Option<bool>,
+  /// `shineout` must use a `-fix` release
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub shineout_version: Option<bool>,
+}
+
+impl PackageJsonPolicy {
+  /// Fields set in `self` win, the rest come from `fallback`
+  pub fn or(self, fallback: PackageJsonPolicy) -> PackageJsonPolicy {
+    PackageJsonPolicy {
+      name: self.name.or(fallback.name),
+      private: self.private.or(fallback.private),
+      package_manager: self.package_manager.or(fallback.package_manager),
+      shineout_version: self.shineout_version.or(fallback.shineout_version),
+    }
+  }
+}
+
+/// The team specific rules of the config file validators
+///
+/// Every field is optional, so policies can be layered: one passed in code, then the `policy`
+/// of spec.json, then the built-in one.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::Policy;
+///
+/// let from_spec: Policy = serde_json::from_str(r#"{ "nodeVersions": ["^22.0.0"] }"#).unwrap();
+/// let builtin = Policy {
+///   node_versions: Some(vec!["^20.9.0".to_string()]),
+///   registries: Some(vec!["https://registry.npmjs.org/".to_string()]),
+///   ..Default::default()
+/// };
+///
+/// let policy = from_spec.or(builtin);
+///
+/// assert_eq!(policy.node_versions, Some(vec!["^22.0.0".to_string()]));
+/// assert_eq!(policy.registries, Some(vec!["https://registry.npmjs.org/".to_string()]));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct Policy {
+  /// Ranges `.node-version` must satisfy one of, empty skips the range check
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub node_versions: Option<Vec<String>>,
+  /// Registries `.npmrc` may point at, empty skips the registry check
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub registries: Option<Vec<String>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub package_json: Option<PackageJsonPolicy>,
+}
+
+impl Policy {
+  /// Fields set in `self` win, the rest come from `fallback`
+  pub fn or(self, fallback: Policy) -> Policy {
+    let package_json = match (self.package_json, fallback.package_json) {
+      (Some(policy), Some(fallback)) => Some(policy.or(fallback)),
+      (policy, fallback) => policy.or(fallback),
+    };
+
+    Policy {
+      node_versions: self.node_versions.or(fallback.node_versions),
+      registries: self.registries.or(fallback.registries),
+      package_json,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_merge_package_json_policy_per_field() {
+    let policy: Policy =
+      serde_json::from_str(r#"{ "packageJson": { "shineoutVersion": false, "private": "off" } }"#)
+        .unwrap();
+    let builtin = Policy {
+      package_json: Some(PackageJsonPolicy {
+        name: Some(true),
+        private: Some(PrivatePolicy::True),
+        package_manager: Some(true),
+        shineout_version: Some(true),
+      }),
+      ..Default::default()
+    };
+
+    let merged = policy.or(builtin).package_json.unwrap();
+
+    assert_eq!(
+      merged,
+      PackageJsonPolicy {
+        name: Some(true),
+        private: Some(PrivatePolicy::Off),
+        package_manager: Some(true),
+        shineout_version: Some(false),
+      }
+    );
+  }
+}
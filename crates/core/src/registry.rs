@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{DiagnosticCode, traits::Validator};
+
+/// What a validator checks, reported by [`Validator::meta`]
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::{DiagnosticCode, ValidatorMeta};
+///
+/// let meta = ValidatorMeta::builder()
+///   .id("npmrc")
+///   .description("Checks the registry of .npmrc")
+///   .codes(vec![DiagnosticCode::shined("npmrc", "missing-registry")])
+///   .fixable(true)
+///   .build();
+///
+/// assert_eq!(meta.id, "npmrc");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorMeta {
+  /// Same as the `validator` of the [`crate::Messages`] it reports
+  #[builder(setter(into))]
+  pub id: String,
+  #[builder(setter(into))]
+  pub description: String,
+  /// The codes the validator can emit
+  #[builder(default)]
+  pub codes: Vec<DiagnosticCode>,
+  /// Whether [`Validator::fix`] can change files
+  #[builder(default = false)]
+  pub fixable: bool,
+}
+
+/// An ordered set of validators, iterated in registration order
+///
+/// Validators must be `Send + Sync` so the registry can run them in parallel. Their
+/// [`ValidatorMeta`] is read once at registration, it can be costly to build, e.g. for lint.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::{Messages, ValidatorError, ValidatorMeta, ValidatorRegistry, traits::Validator};
+///
+/// struct Noop;
+///
+/// impl Validator for Noop {
+///   fn meta(&self) -> ValidatorMeta {
+///     ValidatorMeta::builder().id("noop").description("Does nothing").build()
+///   }
+///   fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+///     Ok(vec![])
+///   }
+///   fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
+///     Ok(vec![])
+///   }
+/// }
+///
+/// let mut registry = ValidatorRegistry::new();
+/// registry.register(Box::new(Noop));
+///
+/// assert!(registry.get("noop").is_some());
+/// assert_eq!(registry.metas()[0].description, "Does nothing");
+/// ```
+#[derive(Default)]
+pub struct ValidatorRegistry {
+  validators: Vec<(ValidatorMeta, Box<dyn Validator + Send + Sync>)>,
+}
+
+impl ValidatorRegistry {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Add a validator, a later validator with the same id replaces the earlier one in place
+  pub fn register(&mut self, validator: Box<dyn Validator + Send + Sync>) -> &mut Self {
+    let meta = validator.meta();
+    match self.validators.iter().position(|(m, _)| m.id == meta.id) {
+      Some(index) => self.validators[index] = (meta, validator),
+      None => self.validators.push((meta, validator)),
+    }
+    self
+  }
+
+  pub fn get(&self, id: &str) -> Option<&(dyn Validator + Send + Sync)> {
+    self
+      .entries()
+      .find(|(meta, _)| meta.id == id)
+      .map(|(_, validator)| validator)
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &(dyn Validator + Send + Sync)> {
+    self.entries().map(|(_, validator)| validator)
+  }
+
+  /// The validators with the meta they had when registered
+  pub fn entries(&self) -> impl Iterator<Item = (&ValidatorMeta, &(dyn Validator + Send + Sync))> {
+    self
+      .validators
+      .iter()
+      .map(|(meta, validator)| (meta, validator.as_ref()))
+  }
+
+  pub fn metas(&self) -> Vec<ValidatorMeta> {
+    self.entries().map(|(meta, _)| meta.clone()).collect()
+  }
+
+  pub fn len(&self) -> usize {
+    self.validators.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.validators.is_empty()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Messages, ValidatorError};
+
+  struct Fake(&'static str, &'static str);
+
+  impl Validator for Fake {
+    fn meta(&self) -> ValidatorMeta {
+      ValidatorMeta::builder()
+        .id(self.0)
+        .description(self.1)
+        .build()
+    }
+
+    fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+      Ok(vec![])
+    }
+
+    fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
+      Ok(vec![])
+    }
+  }
+
+  #[test]
+  fn should_keep_registration_order_and_replace_same_id() {
+    let mut registry = ValidatorRegistry::new();
+    registry
+      .register(Box::new(Fake("npmrc", "a")))
+      .register(Box::new(Fake("lint", "b")))
+      .register(Box::new(Fake("npmrc", "c")));
+
+    let metas = registry.metas();
+    assert_eq!(registry.len(), 2);
+    assert_eq!(metas[0].id, "npmrc");
+    assert_eq!(metas[0].description, "c");
+    assert_eq!(metas[1].id, "lint");
+  }
+
+  #[test]
+  fn should_read_meta_once_at_registration() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Costly;
+
+    impl Validator for Costly {
+      fn meta(&self) -> ValidatorMeta {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        ValidatorMeta::builder()
+          .id("lint")
+          .description("costly")
+          .build()
+      }
+
+      fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+        Ok(vec![])
+      }
+
+      fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
+        Ok(vec![])
+      }
+    }
+
+    let mut registry = ValidatorRegistry::new();
+    registry
+      .register(Box::new(Costly))
+      .register(Box::new(Fake("npmrc", "a")));
+
+    assert!(registry.get("lint").is_some());
+    assert!(registry.get("npmrc").is_some());
+    assert_eq!(registry.metas()[0].description, "costly");
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+  }
+}
@@ -0,0 +1,240 @@
+use std::path::Path;
+
+use miette::Severity;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  Diagnostic, DiagnosticCode, Fix, Messages,
+  loc::{LineIndex, Location},
+  traits::DiagnosticCodeExt,
+};
+
+/// Bumped whenever a field of the JSON report is renamed or removed
+pub const JSON_REPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonSeverity {
+  Error,
+  Warning,
+  Advice,
+}
+
+impl From<Option<Severity>> for JsonSeverity {
+  fn from(severity: Option<Severity>) -> Self {
+    // miette treats a diagnostic without severity as an error
+    match severity {
+      Some(Severity::Advice) => JsonSeverity::Advice,
+      Some(Severity::Warning) => JsonSeverity::Warning,
+      Some(Severity::Error) | None => JsonSeverity::Error,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonLabel {
+  pub label: Option<String>,
+  pub primary: bool,
+  pub offset: usize,
+  pub length: usize,
+  pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonDiagnostic {
+  pub path: String,
+  pub validator: String,
+  /// The workspace package of `path`, left out for the root
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub package: Option<String>,
+  pub code: DiagnosticCode,
+  pub severity: JsonSeverity,
+  pub message: String,
+  pub help: Option<String>,
+  pub url: Option<String>,
+  pub labels: Vec<JsonLabel>,
+  pub fix: Option<Fix>,
+}
+
+impl JsonDiagnostic {
+  pub fn new(path: &str, messages: &Messages, index: &LineIndex, diagnostic: &Diagnostic) -> Self {
+    let labels = diagnostic
+      .labels
+      .iter()
+      .flatten()
+      .map(|label| JsonLabel {
+        label: label.label().map(|s| s.to_string()),
+        primary: label.primary(),
+        offset: label.offset(),
+        length: label.len(),
+        location: index.location(label.offset(), label.len()),
+      })
+      .collect();
+
+    Self {
+      path: path.to_string(),
+      validator: messages.validator_of(diagnostic),
+      package: messages.package.clone(),
+      code: diagnostic.diagnostic_code_or_unknown(),
+      severity: diagnostic.severity.into(),
+      message: diagnostic.message.clone(),
+      help: diagnostic.help.clone(),
+      url: diagnostic.url.clone(),
+      labels,
+      fix: diagnostic.fix.clone(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonSummary {
+  pub files: usize,
+  pub errors: usize,
+  pub warnings: usize,
+  pub advices: usize,
+}
+
+/// A stable, versioned JSON view of validation results
+///
+/// # Examples
+///
+/// ```rust
+/// use std::path::Path;
+///
+/// use doctor_core::{Diagnostic, JsonReport, JsonSeverity, Messages};
+///
+/// let diagnostic = Diagnostic::from(
+///   miette::MietteDiagnostic::new("Missing private field")
+///     .with_code("shined(package-json:missing-private)")
+///     .with_severity(miette::Severity::Warning),
+/// );
+///
+/// let messages = Messages::builder()
+///   .source_code("{}".to_string())
+///   .source_path("/repo/package.json".to_string())
+///   .diagnostics(vec![diagnostic])
+///   .build();
+///
+/// let report = JsonReport::new(&[messages], Path::new("/repo"));
+///
+/// assert_eq!(report.summary.warnings, 1);
+/// assert_eq!(report.diagnostics[0].path, "package.json");
+/// assert_eq!(report.diagnostics[0].severity, JsonSeverity::Warning);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonReport {
+  pub version: u32,
+  pub summary: JsonSummary,
+  pub diagnostics: Vec<JsonDiagnostic>,
+}
+
+impl JsonReport {
+  /// Build the report, file paths are made relative to `root` when possible
+  pub fn new(messages: &[Messages], root: &Path) -> Self {
+    let mut summary = JsonSummary::default();
+    let mut diagnostics = Vec::new();
+
+    for messages in messages.iter().filter(|m| !m.is_empty()) {
+      summary.files += 1;
+
+      let path = messages.relative_path(root);
+
+      let index = messages.line_index();
+
+      for diagnostic in messages.iter() {
+        let diagnostic = JsonDiagnostic::new(&path, messages, &index, diagnostic);
+        match diagnostic.severity {
+          JsonSeverity::Error => summary.errors += 1,
+          JsonSeverity::Warning => summary.warnings += 1,
+          JsonSeverity::Advice => summary.advices += 1,
+        }
+        diagnostics.push(diagnostic);
+      }
+    }
+
+    Self {
+      version: JSON_REPORT_VERSION,
+      summary,
+      diagnostics,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use miette::{LabeledSpan, MietteDiagnostic};
+
+  use super::*;
+  use crate::{Fix, TextEdit};
+
+  #[test]
+  fn should_serialize_stable_report() {
+    let diagnostic = Diagnostic::from(
+      MietteDiagnostic::new("Invalid registry")
+        .with_code("shined(npmrc:invalid-registry)")
+        .with_help("Use the company registry")
+        .with_label(LabeledSpan::at(17..27, "here")),
+    )
+    .with_fix(Fix::from(TextEdit::replace(17..27, "https://b/")));
+
+    let messages = Messages::builder()
+      .source_code("# npmrc\nregistry=https://a/\n".to_string())
+      .source_path("/repo/.npmrc".to_string())
+      .diagnostics(vec![diagnostic])
+      .validator("npmrc")
+      .build();
+
+    let report = JsonReport::new(&[messages], Path::new("/repo"));
+    let json = serde_json::to_value(&report).unwrap();
+
+    assert_eq!(
+      json,
+      serde_json::json!({
+        "version": 1,
+        "summary": { "files": 1, "errors": 1, "warnings": 0, "advices": 0 },
+        "diagnostics": [{
+          "path": ".npmrc",
+          "validator": "npmrc",
+          "code": "shined(npmrc:invalid-registry)",
+          "severity": "error",
+          "message": "Invalid registry",
+          "help": "Use the company registry",
+          "url": null,
+          "labels": [{
+            "label": "here",
+            "primary": false,
+            "offset": 17,
+            "length": 10,
+            "location": {
+              "start": { "row": 2, "col": 10, "byteCol": 10, "utf16Col": 10 },
+              "end": { "row": 2, "col": 20, "byteCol": 20, "utf16Col": 20 }
+            }
+          }],
+          "fix": {
+            "message": null,
+            "edits": [{ "start": 17, "end": 27, "content": "https://b/" }]
+          }
+        }]
+      })
+    );
+
+    let back: JsonReport = serde_json::from_value(json).unwrap();
+    assert_eq!(back, report);
+  }
+
+  #[test]
+  fn should_skip_empty_messages() {
+    let messages = Messages::builder()
+      .source_path("/repo/a.js".to_string())
+      .build();
+
+    let report = JsonReport::new(&[messages], Path::new("/repo"));
+
+    assert_eq!(report.summary, JsonSummary::default());
+    assert!(report.diagnostics.is_empty());
+  }
+}
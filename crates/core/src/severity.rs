@@ -0,0 +1,212 @@
+use std::{
+  collections::BTreeMap,
+  ops::{Deref, DerefMut},
+};
+
+use miette::Severity;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Messages, traits::DiagnosticCodeExt};
+
+/// The level a diagnostic code is configured at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SeverityLevel {
+  Error,
+  Warn,
+  Off,
+}
+
+impl SeverityLevel {
+  /// `None` for [`SeverityLevel::Off`], the diagnostic is dropped
+  pub fn to_severity(self) -> Option<Severity> {
+    match self {
+      SeverityLevel::Error => Some(Severity::Error),
+      SeverityLevel::Warn => Some(Severity::Warning),
+      SeverityLevel::Off => None,
+    }
+  }
+
+  /// The numeric level used by eslint style rule configs
+  pub fn to_rule_level(self) -> u8 {
+    match self {
+      SeverityLevel::Error => 2,
+      SeverityLevel::Warn => 1,
+      SeverityLevel::Off => 0,
+    }
+  }
+}
+
+/// Per-code severity overrides, keyed by a diagnostic code or a glob of codes
+///
+/// `*` matches any run of characters, e.g. `eslint(*)` or `shined(npmrc:*)`. An exact code
+/// wins over globs, and among globs the one with the most literal characters wins.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::{SeverityLevel, SeverityOverrides};
+///
+/// let overrides: SeverityOverrides = serde_json::from_str(
+///   r#"{ "eslint(*)": "warn", "eslint(no-debugger)": "off" }"#,
+/// ).unwrap();
+///
+/// assert_eq!(overrides.level_of("eslint(no-empty)"), Some(SeverityLevel::Warn));
+/// assert_eq!(overrides.level_of("eslint(no-debugger)"), Some(SeverityLevel::Off));
+/// assert_eq!(overrides.level_of("shined(npmrc:invalid-registry)"), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct SeverityOverrides(pub BTreeMap<String, SeverityLevel>);
+
+impl Deref for SeverityOverrides {
+  type Target = BTreeMap<String, SeverityLevel>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl DerefMut for SeverityOverrides {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.0
+  }
+}
+
+impl SeverityOverrides {
+  pub fn level_of(&self, code: &str) -> Option<SeverityLevel> {
+    if let Some(level) = self.get(code) {
+      return Some(*level);
+    }
+
+    self
+      .iter()
+      .filter(|(pattern, _)| pattern.contains('*') && glob_match(pattern, code))
+      // max_by_key 取最后一个最大值，反向遍历让 key 较小的优先
+      .rev()
+      .max_by_key(|(pattern, _)| pattern.chars().filter(|c| *c != '*').count())
+      .map(|(_, level)| *level)
+  }
+
+  /// Re-level the diagnostics of `messages`, dropping the ones turned off
+  pub fn apply(&self, messages: &mut Messages) {
+    if self.is_empty() {
+      return;
+    }
+
+    messages.retain_mut(|diagnostic| {
+      let code = diagnostic.diagnostic_code_or_unknown().to_string();
+      match self.level_of(&code) {
+        Some(level) => match level.to_severity() {
+          Some(severity) => {
+            diagnostic.severity = Some(severity);
+            true
+          }
+          None => false,
+        },
+        None => true,
+      }
+    });
+  }
+}
+
+/// Match `text` against `pattern`, where `*` matches any run of characters
+fn glob_match(pattern: &str, text: &str) -> bool {
+  let mut parts = pattern.split('*');
+
+  // split 至少会产生一个元素
+  let first = parts.next().unwrap_or_default();
+  let Some(mut rest) = text.strip_prefix(first) else {
+    return false;
+  };
+
+  let mut parts = parts.collect::<Vec<_>>();
+  let Some(last) = parts.pop() else {
+    // 没有 `*` ，必须完全相等
+    return rest.is_empty();
+  };
+
+  for part in parts {
+    match rest.find(part) {
+      Some(index) => rest = &rest[index + part.len()..],
+      None => return false,
+    }
+  }
+
+  rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+  use miette::MietteDiagnostic;
+
+  use super::*;
+  use crate::Diagnostic;
+
+  fn overrides(pairs: &[(&str, SeverityLevel)]) -> SeverityOverrides {
+    SeverityOverrides(
+      pairs
+        .iter()
+        .map(|(code, level)| (code.to_string(), *level))
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn should_match_globs() {
+    assert!(glob_match("eslint(*)", "eslint(no-debugger)"));
+    assert!(glob_match("*", "shined(npmrc:invalid-registry)"));
+    assert!(glob_match(
+      "shined(*:config-file-not-found)",
+      "shined(npmrc:config-file-not-found)"
+    ));
+    assert!(!glob_match(
+      "eslint(*)",
+      "eslint-plugin-react(jsx-no-undef)"
+    ));
+    assert!(!glob_match("a*a", "a"));
+  }
+
+  #[test]
+  fn should_prefer_exact_then_most_specific_glob() {
+    let overrides = overrides(&[
+      ("*", SeverityLevel::Warn),
+      ("shined(npmrc:*)", SeverityLevel::Off),
+      ("shined(npmrc:missing-registry)", SeverityLevel::Error),
+    ]);
+
+    assert_eq!(
+      overrides.level_of("shined(npmrc:missing-registry)"),
+      Some(SeverityLevel::Error)
+    );
+    assert_eq!(
+      overrides.level_of("shined(npmrc:invalid-registry)"),
+      Some(SeverityLevel::Off)
+    );
+    assert_eq!(
+      overrides.level_of("eslint(no-debugger)"),
+      Some(SeverityLevel::Warn)
+    );
+  }
+
+  #[test]
+  fn should_relevel_and_drop_diagnostics() {
+    let mut messages = Messages::builder()
+      .diagnostics(vec![
+        Diagnostic::from(MietteDiagnostic::new("a").with_code("eslint(no-debugger)")),
+        Diagnostic::from(MietteDiagnostic::new("b").with_code("eslint(no-empty)")),
+        Diagnostic::from(MietteDiagnostic::new("c").with_code("shined(npmrc:invalid-registry)")),
+      ])
+      .build();
+
+    overrides(&[
+      ("eslint(*)", SeverityLevel::Warn),
+      ("eslint(no-empty)", SeverityLevel::Off),
+    ])
+    .apply(&mut messages);
+
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].severity, Some(Severity::Warning));
+    assert_eq!(messages[1].severity, None);
+  }
+}
@@ -0,0 +1,469 @@
+use std::{collections::BTreeMap, ops::Range, path::Path};
+
+use miette::{LabeledSpan, MietteDiagnostic, Severity};
+use oxc::{allocator::Allocator, parser::Parser};
+
+use crate::{
+  Diagnostic, DiagnosticCode, Messages, ValidatorMeta, hack_source_type_from_path, loc::LineIndex,
+  traits::DiagnosticCodeExt,
+};
+
+/// The validator of diagnostics doctor reports about itself, e.g. unused suppressions
+pub const DOCTOR_VALIDATOR: &str = "doctor";
+
+const DIRECTIVE: &str = "doctor-disable";
+const NEXT_LINE: &str = "-next-line";
+const COMMENT_MARKERS: [&str; 3] = ["//", "/*", "#"];
+const SOURCE_EXTENSIONS: [&str; 8] = ["js", "mjs", "cjs", "jsx", "ts", "mts", "cts", "tsx"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionKind {
+  /// `// doctor-disable-next-line <code>`
+  NextLine,
+  /// `/* doctor-disable <code> */`, applies to the whole file
+  File,
+}
+
+/// A suppression directive found in a comment
+///
+/// Codes are matched against the full diagnostic code, e.g. `shined(npmrc:invalid-registry)`,
+/// a directive without codes suppresses everything in its scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+  pub kind: SuppressionKind,
+  pub codes: Vec<String>,
+  /// 1-based line of the directive
+  pub row: usize,
+  /// Byte range of the directive, starting at the comment marker
+  pub span: Range<usize>,
+}
+
+/// The validators whose results are passed to [`apply_suppressions_all`]
+///
+/// A directive can only be reported as unused when the validator that reports its code ran,
+/// e.g. an `eslint(no-debugger)` directive is not unused when only syntax was checked.
+#[derive(Debug, Clone, Copy)]
+pub enum SuppressionScope<'a> {
+  /// Every validator of the project ran
+  All,
+  /// Only these validators ran, directives without codes are never reported as unused
+  Only(&'a [&'a ValidatorMeta]),
+}
+
+impl SuppressionScope<'_> {
+  fn reports(&self, code: Option<&str>) -> bool {
+    let validators = match self {
+      SuppressionScope::All => return true,
+      SuppressionScope::Only(validators) => validators,
+    };
+    let Some(Ok(code)) = code.map(|code| code.parse::<DiagnosticCode>()) else {
+      return false;
+    };
+
+    validators.iter().any(|meta| {
+      meta.codes.contains(&code)
+        || (code.is_shined() && code.validator.as_deref() == Some(meta.id.as_str()))
+    })
+  }
+}
+
+impl Suppression {
+  fn matches(&self, code: &str, row: Option<usize>) -> bool {
+    let in_scope = match self.kind {
+      SuppressionKind::File => true,
+      SuppressionKind::NextLine => row == Some(self.row + 1),
+    };
+    in_scope && (self.codes.is_empty() || self.codes.iter().any(|c| c == code))
+  }
+}
+
+/// Find all suppression directives in the comments of `source`, the file at `path`
+///
+/// JavaScript and TypeScript comments come from the parser, `.npmrc` has `#` line comments
+/// and other files have none. A directive must start its comment, a `--` ends the list of
+/// codes so a reason can be given.
+pub fn parse_suppressions(path: &Path, source: &str) -> Vec<Suppression> {
+  let index = LineIndex::new(source);
+
+  comments(path, source, &index)
+    .into_iter()
+    .filter_map(|range| parse_comment(&source[range.clone()], range.start, &index))
+    .collect()
+}
+
+/// Byte ranges of the comments of `source`, each starting at its comment marker
+fn comments(path: &Path, source: &str, index: &LineIndex) -> Vec<Range<usize>> {
+  if path.file_name().is_some_and(|name| name == ".npmrc") {
+    return index
+      .lines()
+      .filter_map(|line| {
+        let text = &source[line.clone()];
+        let marker = text.len() - text.trim_start().len();
+        text[marker..]
+          .starts_with('#')
+          .then_some(line.start + marker..line.end)
+      })
+      .collect();
+  }
+
+  let is_source = path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext));
+  if !is_source {
+    return vec![];
+  }
+
+  // 用解析器拿注释，字符串里的 `//` 不算
+  let allocator = Allocator::default();
+  let parse = Parser::new(&allocator, source, hack_source_type_from_path(path)).parse();
+  parse
+    .program
+    .comments
+    .iter()
+    .map(|comment| comment.span.start as usize..comment.span.end as usize)
+    .collect()
+}
+
+fn parse_comment(comment: &str, start: usize, index: &LineIndex) -> Option<Suppression> {
+  let marker = COMMENT_MARKERS
+    .iter()
+    .find(|marker| comment.starts_with(**marker))?;
+
+  let rest = comment[marker.len()..].trim_start();
+  let mut rest = rest.strip_prefix(DIRECTIVE)?;
+  let kind = if let Some(after) = rest.strip_prefix(NEXT_LINE) {
+    rest = after;
+    SuppressionKind::NextLine
+  } else {
+    SuppressionKind::File
+  };
+
+  // `doctor-disabled` 之类的不是指令
+  if rest
+    .chars()
+    .next()
+    .is_some_and(|c| !c.is_whitespace() && c != '*')
+  {
+    return None;
+  }
+
+  let body = rest.split("*/").next().unwrap_or_default();
+  let body = body.split("--").next().unwrap_or_default();
+
+  let codes = body
+    .split(|c: char| c == ',' || c.is_whitespace())
+    .filter(|code| !code.is_empty())
+    .map(|code| code.to_string())
+    .collect();
+
+  Some(Suppression {
+    kind,
+    codes,
+    row: index.position(start).row,
+    span: start..start + comment.trim_end().len(),
+  })
+}
+
+fn unused_suppression(suppression: &Suppression, code: Option<&str>) -> Diagnostic {
+  let message = match code {
+    Some(code) => format!(
+      "Unused doctor-disable directive, no `{}` problem was reported",
+      code
+    ),
+    None => "Unused doctor-disable directive, no problem was reported".to_string(),
+  };
+
+  let span = suppression.span.clone();
+
+  Diagnostic::from(
+    MietteDiagnostic::new(message)
+      .with_code(DiagnosticCode::shined(
+        DOCTOR_VALIDATOR,
+        "unused-suppression",
+      ))
+      .with_severity(Severity::Warning)
+      .with_help("Remove the directive")
+      .with_label(LabeledSpan::at(span, "unused directive")),
+  )
+}
+
+/// Drop the diagnostics of `messages` silenced by its suppression directives
+///
+/// Directives, or codes of a directive, that silence nothing are reported as
+/// `shined(doctor:unused-suppression)` warnings.
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_core::{Diagnostic, Messages, apply_suppressions};
+/// use miette::{LabeledSpan, MietteDiagnostic};
+///
+/// let source = "# doctor-disable-next-line shined(npmrc:invalid-registry)\nregistry=http://a/\n";
+///
+/// let mut messages = Messages::builder()
+///   .source_path(".npmrc".to_string())
+///   .source_code(source.to_string())
+///   .diagnostics(vec![Diagnostic::from(
+///     MietteDiagnostic::new("Invalid registry")
+///       .with_code("shined(npmrc:invalid-registry)")
+///       .with_label(LabeledSpan::at(67..76, "")),
+///   )])
+///   .build();
+///
+/// apply_suppressions(&mut messages);
+///
+/// assert!(messages.is_empty());
+/// ```
+pub fn apply_suppressions(messages: &mut Messages) {
+  apply_suppressions_all([messages], SuppressionScope::All);
+}
+
+/// Like [`apply_suppressions`], over the results of several validators at once
+///
+/// Messages are merged by `source_path`, so a directive used by the lint results of a file
+/// is not reported as unused by its syntax results. Unused directives are reported in the
+/// first messages of their file, as far as `scope` knows the validator of their code.
+pub fn apply_suppressions_all<'a>(
+  messages: impl IntoIterator<Item = &'a mut Messages>,
+  scope: SuppressionScope,
+) {
+  let mut files: BTreeMap<String, Vec<&'a mut Messages>> = BTreeMap::new();
+  for messages in messages {
+    files
+      .entry(messages.source_path.clone())
+      .or_default()
+      .push(messages);
+  }
+
+  for (path, mut group) in files {
+    // 同一个文件的 source_code 相同，校验器崩溃时可能是空的
+    let Some(source) = group
+      .iter()
+      .map(|messages| messages.source_code.as_str())
+      .find(|source| !source.is_empty())
+    else {
+      continue;
+    };
+    let suppressions = parse_suppressions(Path::new(&path), source);
+
+    if suppressions.is_empty() {
+      continue;
+    }
+
+    // 每条指令里每个 code 是否被用到，没有 code 的指令占一个位置
+    let mut used = suppressions
+      .iter()
+      .map(|s| vec![false; s.codes.len().max(1)])
+      .collect::<Vec<_>>();
+
+    for messages in group.iter_mut() {
+      suppress(messages, &suppressions, &mut used);
+    }
+
+    let first = &mut group[0];
+    for (suppression, used) in suppressions.iter().zip(used) {
+      if suppression.codes.is_empty() {
+        if !used[0] && scope.reports(None) {
+          first.push(unused_suppression(suppression, None));
+        }
+        continue;
+      }
+      for (code, used) in suppression.codes.iter().zip(used) {
+        if !used && scope.reports(Some(code)) {
+          first.push(unused_suppression(suppression, Some(code)));
+        }
+      }
+    }
+  }
+}
+
+/// Drop the diagnostics of `messages` matched by `suppressions`, marking the codes in `used`
+fn suppress(messages: &mut Messages, suppressions: &[Suppression], used: &mut [Vec<bool>]) {
+  let index = messages.line_index();
+  let mut keep = Vec::with_capacity(messages.len());
+
+  for diagnostic in messages.iter() {
+    let code = diagnostic.diagnostic_code_or_unknown().to_string();
+    let row = diagnostic
+      .primary_location(&index)
+      .map(|location| location.start.row);
+
+    let mut suppressed = false;
+    for (i, suppression) in suppressions.iter().enumerate() {
+      if suppression.matches(&code, row) {
+        suppressed = true;
+        let position = suppression
+          .codes
+          .iter()
+          .position(|c| *c == code)
+          .unwrap_or(0);
+        used[i][position] = true;
+      }
+    }
+    keep.push(!suppressed);
+  }
+
+  let mut keep = keep.into_iter();
+  messages.retain(|_| keep.next().unwrap_or(true));
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::messages;
+
+  fn diagnostic(code: &str, span: Option<Range<usize>>) -> Diagnostic {
+    let mut diagnostic = MietteDiagnostic::new("problem").with_code(code);
+    if let Some(span) = span {
+      diagnostic = diagnostic.with_label(LabeledSpan::at(span, ""));
+    }
+    Diagnostic::from(diagnostic)
+  }
+
+  fn codes(messages: &Messages) -> Vec<String> {
+    messages
+      .iter()
+      .map(|d| d.diagnostic_code_or_unknown().to_string())
+      .collect()
+  }
+
+  #[test]
+  fn should_parse_directives() {
+    let source = "// doctor-disable-next-line a(b), c(d) -- legacy\n/* doctor-disable e(f) */\n// doctor-disable\nconst doctorDisabled = 1; // doctor-disabled\n";
+
+    let suppressions = parse_suppressions(Path::new("a.js"), source);
+
+    assert_eq!(suppressions.len(), 3);
+    assert_eq!(suppressions[0].kind, SuppressionKind::NextLine);
+    assert_eq!(suppressions[0].codes, vec!["a(b)", "c(d)"]);
+    assert_eq!(suppressions[0].row, 1);
+    assert_eq!(suppressions[1].kind, SuppressionKind::File);
+    assert_eq!(suppressions[1].codes, vec!["e(f)"]);
+    assert_eq!(
+      &source[suppressions[1].span.clone()],
+      "/* doctor-disable e(f) */"
+    );
+    assert!(suppressions[2].codes.is_empty());
+  }
+
+  #[test]
+  fn should_ignore_directive_outside_comment() {
+    let source = "const a = '// doctor-disable x(y)';\n# doctor-disable\n";
+
+    assert!(parse_suppressions(Path::new("a.js"), source).is_empty());
+    assert!(parse_suppressions(Path::new("package.json"), "// doctor-disable").is_empty());
+  }
+
+  #[test]
+  fn should_only_accept_hash_comments_in_npmrc() {
+    let source = "registry=https://a/ # doctor-disable\n  # doctor-disable x(y)\n";
+
+    let suppressions = parse_suppressions(Path::new("/repo/.npmrc"), source);
+
+    assert_eq!(suppressions.len(), 1);
+    assert_eq!(suppressions[0].codes, vec!["x(y)"]);
+    assert_eq!(suppressions[0].row, 2);
+  }
+
+  #[test]
+  fn should_count_rows_like_line_index() {
+    let source = "let a = 1;\r// doctor-disable-next-line x(y)\rfoo();\n";
+
+    let suppressions = parse_suppressions(Path::new("a.js"), source);
+
+    assert_eq!(suppressions[0].row, 2);
+  }
+
+  #[test]
+  fn should_suppress_next_line_only() {
+    let source = "// doctor-disable-next-line shined(syntax:parse-error)\nlet = ;\nlet = ;\n";
+    let mut messages = messages(
+      "lint",
+      "/repo/a.js",
+      source,
+      vec![
+        diagnostic("shined(syntax:parse-error)", Some(59..60)),
+        diagnostic("shined(syntax:parse-error)", Some(67..68)),
+      ],
+    );
+
+    apply_suppressions(&mut messages);
+
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].labels.as_ref().unwrap()[0].offset(), 67);
+  }
+
+  #[test]
+  fn should_suppress_whole_file_including_unlabelled() {
+    let source = "/* doctor-disable shined(npmrc:missing-registry) */\n";
+    let mut messages = messages(
+      "lint",
+      "/repo/a.js",
+      source,
+      vec![
+        diagnostic("shined(npmrc:missing-registry)", None),
+        diagnostic("shined(npmrc:invalid-registry)", None),
+      ],
+    );
+
+    apply_suppressions(&mut messages);
+
+    assert_eq!(codes(&messages), vec!["shined(npmrc:invalid-registry)"]);
+  }
+
+  #[test]
+  fn should_report_unused_codes() {
+    let source = "// doctor-disable-next-line a(b), c(d)\nfoo\n";
+    let mut messages = messages(
+      "lint",
+      "/repo/a.js",
+      source,
+      vec![diagnostic("a(b)", Some(39..42))],
+    );
+
+    apply_suppressions(&mut messages);
+
+    assert_eq!(codes(&messages), vec!["shined(doctor:unused-suppression)"]);
+    assert!(messages[0].message.contains("c(d)"));
+    assert_eq!(messages[0].severity, Some(Severity::Warning));
+  }
+
+  #[test]
+  fn should_merge_validators_of_one_file() {
+    let source = "// doctor-disable-next-line eslint(no-debugger)\ndebugger;\n";
+    let file = |validator: &str, diagnostics| {
+      Messages::builder()
+        .source_code(source.to_string())
+        .source_path("/repo/a.js".to_string())
+        .validator(validator)
+        .diagnostics(diagnostics)
+        .build()
+    };
+    let mut lint = file(
+      "lint",
+      vec![diagnostic("eslint(no-debugger)", Some(48..57))],
+    );
+    let mut syntax = file("syntax", vec![]);
+
+    apply_suppressions_all([&mut lint, &mut syntax], SuppressionScope::All);
+
+    assert!(lint.is_empty());
+    assert!(syntax.is_empty());
+  }
+
+  #[test]
+  fn should_only_report_unused_codes_of_validators_that_ran() {
+    let source = "// doctor-disable-next-line eslint(no-debugger), shined(syntax:parse-error)\n// doctor-disable-next-line\ndebugger;\n";
+    let syntax = ValidatorMeta::builder()
+      .id("syntax")
+      .description("syntax")
+      .build();
+    let mut messages = messages("syntax", "/repo/a.js", source, vec![]);
+
+    apply_suppressions_all([&mut messages], SuppressionScope::Only(&[&syntax]));
+
+    assert_eq!(codes(&messages), vec!["shined(doctor:unused-suppression)"]);
+    assert!(messages[0].message.contains("shined(syntax:parse-error)"));
+  }
+}
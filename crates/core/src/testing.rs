@@ -0,0 +1,33 @@
+//! Fixtures shared by the tests of the doctor crates, enabled by the `testing` feature
+
+use std::{fs, path::PathBuf};
+
+use crate::{Diagnostic, Messages};
+
+/// A fresh directory in the temp dir holding `files`, `name` must be unique per test binary
+pub fn project(name: &str, files: &[(&str, &str)]) -> PathBuf {
+  let dir = std::env::temp_dir().join(format!("doctor-{name}-{}", std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  for (path, content) in files {
+    let path = dir.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+  }
+  dir
+}
+
+/// The `diagnostics` `validator` reported for the file at `path` with `source`
+pub fn messages(
+  validator: &str,
+  path: &str,
+  source: &str,
+  diagnostics: Vec<Diagnostic>,
+) -> Messages {
+  Messages::builder()
+    .source_code(source.to_string())
+    .source_path(path.to_string())
+    .diagnostics(diagnostics)
+    .validator(validator)
+    .build()
+}
@@ -0,0 +1,21 @@
+use miette::MietteDiagnostic;
+
+use crate::DiagnosticCode;
+
+pub trait DiagnosticCodeExt {
+  /// The parsed code, `None` when the diagnostic has no code or it is malformed
+  fn diagnostic_code(&self) -> Option<DiagnosticCode>;
+
+  /// Like [`DiagnosticCodeExt::diagnostic_code`], falling back to `unknown(unknown)`
+  fn diagnostic_code_or_unknown(&self) -> DiagnosticCode {
+    self
+      .diagnostic_code()
+      .unwrap_or_else(DiagnosticCode::unknown)
+  }
+}
+
+impl DiagnosticCodeExt for MietteDiagnostic {
+  fn diagnostic_code(&self) -> Option<DiagnosticCode> {
+    self.code.as_deref().and_then(|code| code.parse().ok())
+  }
+}
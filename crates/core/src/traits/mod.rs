@@ -1,5 +1,7 @@
+mod diagnostic_code;
 mod path;
 mod validator;
 
+pub use diagnostic_code::*;
 pub use path::*;
 pub use validator::*;
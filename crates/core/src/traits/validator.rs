@@ -1,4 +1,4 @@
-use crate::{Messages, ValidatorError};
+use crate::{Messages, Patch, ValidatorError, ValidatorMeta};
 
 /// A trait for types that can validate configuration files or other resources
 ///
@@ -6,11 +6,17 @@ use crate::{Messages, ValidatorError};
 ///
 /// ```rust
 /// use doctor_core::traits::Validator;
-/// use doctor_core::{Messages, ValidatorError};
+/// use doctor_core::{Messages, ValidatorError, ValidatorMeta};
 ///
 /// struct MyValidator;
 ///
 /// impl Validator for MyValidator {
+///     fn meta(&self) -> ValidatorMeta {
+///         ValidatorMeta::builder()
+///             .id("my-validator")
+///             .description("Checks my config")
+///             .build()
+///     }
 ///     fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
 ///         // Validation logic here
 ///         Ok(vec![])
@@ -22,6 +28,27 @@ use crate::{Messages, ValidatorError};
 /// }
 /// ```
 pub trait Validator {
+  /// The id, description and codes of the validator
+  fn meta(&self) -> ValidatorMeta;
+
   fn validate(&self) -> Result<Vec<Messages>, ValidatorError>;
   fn fix(&self) -> Result<Vec<Messages>, ValidatorError>;
+
+  /// Work other validators wait for, run before validators run in parallel
+  ///
+  /// An error here is reported again by [`Validator::validate`], so it can be ignored.
+  fn prepare(&self) -> Result<(), ValidatorError> {
+    Ok(())
+  }
+
+  /// The messages with every fix [`Validator::fix`] would apply, without writing anything
+  fn fixes(&self) -> Result<Vec<Messages>, ValidatorError> {
+    self.validate()
+  }
+
+  /// Collect the changes [`Validator::fix`] would make as a [`Patch`], without writing anything
+  fn fix_dry_run(&self) -> Result<Patch, ValidatorError> {
+    let messages = self.validate()?;
+    Ok(Patch::from_messages(&messages))
+  }
 }
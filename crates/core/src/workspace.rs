@@ -0,0 +1,382 @@
+use std::{
+  fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{Messages, ValidatorError};
+
+/// A package of a monorepo, see [`Workspace::discover`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspacePackage {
+  /// `name` of its package.json, the directory relative to the root when it has none
+  pub name: String,
+  pub dir: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct PnpmWorkspace {
+  #[serde(default)]
+  packages: Option<Vec<String>>,
+}
+
+/// The packages of a monorepo, empty for a single package project
+///
+/// # Examples
+///
+/// ```rust
+/// use std::path::Path;
+///
+/// use doctor_core::{Messages, Workspace, WorkspacePackage};
+///
+/// let workspace = Workspace {
+///   root: "/repo".into(),
+///   packages: vec![WorkspacePackage { name: "@repo/a".to_string(), dir: "/repo/packages/a".into() }],
+/// };
+///
+/// let mut messages = Messages::builder().source_path("/repo/packages/a/src/index.js".to_string()).build();
+/// workspace.tag(&mut messages);
+///
+/// assert_eq!(messages.package.as_deref(), Some("@repo/a"));
+/// assert!(workspace.package_of(Path::new("/repo/.npmrc")).is_none());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Workspace {
+  pub root: PathBuf,
+  /// Sorted by directory
+  pub packages: Vec<WorkspacePackage>,
+}
+
+fn invalid(message: String) -> ValidatorError {
+  ValidatorError::Unknown(message.into())
+}
+
+impl Workspace {
+  /// Find the packages listed by `pnpm-workspace.yaml`, or by `workspaces` of package.json
+  /// when there is no such file
+  ///
+  /// `!` patterns exclude packages, `node_modules` and hidden directories are never searched.
+  /// Only the directories a pattern can reach are read, see [`Workspace::bases`].
+  pub fn discover(root: impl AsRef<Path>) -> Result<Self, ValidatorError> {
+    let root = root.as_ref();
+    let patterns = Self::patterns(root)?;
+
+    let mut packages = vec![];
+    if !patterns.is_empty() {
+      let (include, exclude) = Self::globs(&patterns)?;
+      for (base, depth) in Self::bases(&patterns) {
+        let dir = root.join(base);
+        if dir.is_dir() {
+          Self::collect(root, &dir, depth, &include, &exclude, &mut packages);
+        }
+      }
+    }
+    // 模式之间可能有重叠，比如 `packages/*` 和 `packages/**`
+    packages.sort_by(|a, b| a.dir.cmp(&b.dir));
+    packages.dedup_by(|a, b| a.dir == b.dir);
+
+    Ok(Self {
+      root: root.to_path_buf(),
+      packages,
+    })
+  }
+
+  /// The file the package patterns are read from, `pnpm-workspace.yaml` when there is one
+  pub fn config_path(root: impl AsRef<Path>) -> PathBuf {
+    let root = root.as_ref();
+    let pnpm = root.join("pnpm-workspace.yaml");
+    if pnpm.is_file() {
+      pnpm
+    } else {
+      root.join("package.json")
+    }
+  }
+
+  fn patterns(root: &Path) -> Result<Vec<String>, ValidatorError> {
+    let pnpm = root.join("pnpm-workspace.yaml");
+    match fs::read_to_string(&pnpm) {
+      Ok(source) if source.trim().is_empty() => return Ok(vec![]),
+      Ok(source) => {
+        let workspace = serde_yaml_ng::from_str::<PnpmWorkspace>(&source)
+          .map_err(|e| invalid(format!("Invalid {}: {e}", pnpm.display())))?;
+        return Ok(workspace.packages.unwrap_or_default());
+      }
+      Err(e) if e.kind() == ErrorKind::NotFound => {}
+      Err(e) => return Err(e.into()),
+    }
+
+    let source = match fs::read_to_string(root.join("package.json")) {
+      Ok(source) => source,
+      Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+      Err(e) => return Err(e.into()),
+    };
+    // 写坏的 package.json 由 package-json 校验器报告，这里当作没有 workspaces
+    let Ok(package_json) = serde_json::from_str::<Value>(&source) else {
+      return Ok(vec![]);
+    };
+
+    // npm 是数组，yarn 还支持 { "packages": [...] }
+    let workspaces = match package_json.get("workspaces") {
+      Some(Value::Object(workspaces)) => workspaces.get("packages"),
+      workspaces => workspaces,
+    };
+
+    Ok(
+      workspaces
+        .and_then(Value::as_array)
+        .map(|patterns| {
+          patterns
+            .iter()
+            .filter_map(|pattern| pattern.as_str().map(String::from))
+            .collect()
+        })
+        .unwrap_or_default(),
+    )
+  }
+
+  /// Include and exclude sets of `patterns`, matched against `/` separated relative paths
+  fn globs(patterns: &[String]) -> Result<(GlobSet, GlobSet), ValidatorError> {
+    let mut include = GlobSetBuilder::new();
+    let mut exclude = GlobSetBuilder::new();
+
+    for pattern in patterns {
+      let (builder, pattern) = match pattern.strip_prefix('!') {
+        Some(pattern) => (&mut exclude, pattern),
+        None => (&mut include, pattern.as_str()),
+      };
+      let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+      let glob = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| invalid(format!("Invalid workspace pattern `{pattern}`: {e}")))?;
+      builder.add(glob);
+    }
+
+    let build = |builder: GlobSetBuilder| {
+      builder
+        .build()
+        .map_err(|e| invalid(format!("Invalid workspace patterns: {e}")))
+    };
+    Ok((build(include)?, build(exclude)?))
+  }
+
+  /// The literal directory each include pattern starts from, and how many levels below it the
+  /// pattern can match, `None` when it contains `**`
+  ///
+  /// ```text
+  /// packages/*        -> (packages, Some(1))
+  /// apps/*/sites/*    -> (apps, Some(3))
+  /// tools/**          -> (tools, None)
+  /// ```
+  fn bases(patterns: &[String]) -> Vec<(String, Option<usize>)> {
+    let is_glob = |component: &str| component.contains(['*', '?', '[', '{', '\\']);
+
+    let mut bases = patterns
+      .iter()
+      .filter(|pattern| !pattern.starts_with('!'))
+      .map(|pattern| {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        let components = pattern.split('/').collect::<Vec<_>>();
+        let literal = components
+          .iter()
+          .take_while(|component| !is_glob(component))
+          .count();
+        let rest = &components[literal..];
+        let depth = (!rest.contains(&"**")).then_some(rest.len());
+        // 全是字面量的模式就是包目录本身，从它的父目录往下找一层
+        let (base, depth) = match depth {
+          Some(0) if literal > 0 => (&components[..literal - 1], Some(1)),
+          _ => (&components[..literal], depth),
+        };
+        (base.join("/"), depth)
+      })
+      .collect::<Vec<_>>();
+    bases.sort();
+    bases.dedup();
+    bases
+  }
+
+  /// `depth` is how many levels below `dir` are searched, `None` for no limit
+  ///
+  /// Directories that can not be read are skipped, the packages found elsewhere still count.
+  fn collect(
+    root: &Path,
+    dir: &Path,
+    depth: Option<usize>,
+    include: &GlobSet,
+    exclude: &GlobSet,
+    packages: &mut Vec<WorkspacePackage>,
+  ) {
+    if depth == Some(0) {
+      return;
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+      return;
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+      let name = entry.file_name();
+      let name = name.to_string_lossy();
+      // 不跟随软链接，pnpm 的 node_modules 里全是
+      let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+      if !is_dir || name == "node_modules" || name.starts_with('.') {
+        continue;
+      }
+
+      let path = entry.path();
+      let relative = path
+        .strip_prefix(root)
+        .unwrap_or(&path)
+        .to_string_lossy()
+        .replace('\\', "/");
+
+      let package_json = path.join("package.json");
+      if include.is_match(&relative) && !exclude.is_match(&relative) && package_json.is_file() {
+        let name = fs::read_to_string(&package_json)
+          .ok()
+          .and_then(|source| serde_json::from_str::<Value>(&source).ok())
+          .and_then(|package_json| package_json.get("name")?.as_str().map(String::from))
+          .unwrap_or_else(|| relative.clone());
+        packages.push(WorkspacePackage {
+          name,
+          dir: path.clone(),
+        });
+      }
+
+      let depth = depth.map(|depth| depth - 1);
+      Self::collect(root, &path, depth, include, exclude, packages);
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.packages.is_empty()
+  }
+
+  /// The innermost package containing `path`, `None` for files that belong to the root
+  pub fn package_of(&self, path: &Path) -> Option<&WorkspacePackage> {
+    self
+      .packages
+      .iter()
+      .filter(|package| path.starts_with(&package.dir))
+      .max_by_key(|package| package.dir.components().count())
+  }
+
+  /// Set the `package` of `messages` from its `source_path`, unless it is set already
+  pub fn tag(&self, messages: &mut Messages) {
+    if messages.package.is_none() {
+      messages.package = self
+        .package_of(Path::new(&messages.source_path))
+        .map(|package| package.name.clone());
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::project;
+
+  fn names(workspace: &Workspace) -> Vec<&str> {
+    workspace
+      .packages
+      .iter()
+      .map(|package| package.name.as_str())
+      .collect()
+  }
+
+  #[test]
+  fn should_discover_pnpm_workspace_packages() {
+    let dir = project(
+      "workspace-pnpm",
+      &[
+        (
+          "pnpm-workspace.yaml",
+          "packages:\n  - 'packages/*'\n  - \"apps/**\"\n  - '!packages/ignored'\n",
+        ),
+        ("package.json", r#"{ "workspaces": ["nope/*"] }"#),
+        ("packages/a/package.json", r#"{ "name": "@repo/a" }"#),
+        ("packages/b/package.json", "{}"),
+        ("packages/ignored/package.json", r#"{ "name": "ignored" }"#),
+        (
+          "packages/a/node_modules/dep/package.json",
+          r#"{ "name": "dep" }"#,
+        ),
+        (
+          "packages/a/nested/package.json",
+          r#"{ "name": "too-deep" }"#,
+        ),
+        ("apps/web/site/package.json", r#"{ "name": "site" }"#),
+        ("nope/x/package.json", r#"{ "name": "x" }"#),
+      ],
+    );
+
+    let workspace = Workspace::discover(&dir).unwrap();
+
+    assert_eq!(names(&workspace), vec!["site", "@repo/a", "packages/b"]);
+    assert_eq!(
+      workspace
+        .package_of(&dir.join("packages/a/src/index.js"))
+        .map(|package| package.name.as_str()),
+      Some("@repo/a")
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn should_only_search_where_patterns_reach() {
+    let patterns = [
+      "packages/*",
+      "apps/*/sites/*",
+      "./tools/**/",
+      "docs",
+      "*",
+      "!packages/x",
+    ]
+    .map(String::from);
+
+    assert_eq!(
+      Workspace::bases(&patterns),
+      vec![
+        ("".to_string(), Some(1)),
+        ("apps".to_string(), Some(3)),
+        ("packages".to_string(), Some(1)),
+        ("tools".to_string(), None),
+      ]
+    );
+  }
+
+  #[test]
+  fn should_discover_package_json_workspaces() {
+    let dir = project(
+      "workspace-yarn",
+      &[
+        (
+          "package.json",
+          r#"{ "workspaces": { "packages": ["packages/*"] } }"#,
+        ),
+        ("packages/a/package.json", r#"{ "name": "a" }"#),
+      ],
+    );
+
+    assert_eq!(names(&Workspace::discover(&dir).unwrap()), vec!["a"]);
+
+    fs::write(dir.join("package.json"), r#"{ "name": "single" }"#).unwrap();
+    assert!(Workspace::discover(&dir).unwrap().is_empty());
+    assert_eq!(Workspace::config_path(&dir), dir.join("package.json"));
+
+    fs::write(dir.join("pnpm-workspace.yaml"), "packages: [").unwrap();
+    assert!(Workspace::discover(&dir).is_err());
+    assert_eq!(
+      Workspace::config_path(&dir),
+      dir.join("pnpm-workspace.yaml")
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
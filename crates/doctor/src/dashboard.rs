@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-use doctor_core::Messages;
+use doctor_core::{DiagnosticCode, Messages, traits::DiagnosticCodeExt};
 use tabled::{Table, Tabled};
 
 #[derive(Tabled)]
@@ -11,6 +11,17 @@ pub struct Row {
   pub count: usize,
 }
 
+#[derive(Tabled)]
+pub struct PackageRow {
+  #[tabled(rename = "package")]
+  pub package: String,
+  #[tabled(rename = "count")]
+  pub count: usize,
+}
+
+/// Name of the root package in [`MessagesDashboard::count_by_package`]
+pub const ROOT_PACKAGE: &str = "(root)";
+
 pub struct MessagesDashboard<'a>(&'a [Messages]);
 
 impl<'a> MessagesDashboard<'a> {
@@ -18,68 +29,102 @@ impl<'a> MessagesDashboard<'a> {
     Self(messages)
   }
 
-  pub fn get_report(&self) -> Vec<String> {
-    let mut reports = Vec::new();
-
-    let mut count_map = HashMap::new();
+  /// Count the diagnostics of messages with errors, grouped by code
+  pub fn count_by_code(&self) -> BTreeMap<DiagnosticCode, usize> {
+    let mut count_map = BTreeMap::new();
     for msg in self.0 {
       if msg.has_error() {
         for item in &msg.diagnostics {
           *count_map
-            .entry(item.code.clone().unwrap_or("unknown".to_string()))
+            .entry(item.diagnostic_code_or_unknown())
             .or_insert(0) += 1;
         }
       }
     }
-    let mut ts = vec![];
+    count_map
+  }
 
-    for (key, value) in count_map {
-      ts.push(Row {
-        name: key,
-        count: value,
-      });
+  /// Like [`MessagesDashboard::count_by_code`], grouped by [`DiagnosticCode::group`]
+  pub fn count_by_group(&self) -> BTreeMap<String, usize> {
+    let mut count_map = BTreeMap::new();
+    for (code, count) in self.count_by_code() {
+      *count_map.entry(code.group().to_string()).or_insert(0) += count;
     }
-
-    if !ts.is_empty() {
-      let table = Table::new(ts);
-      let table_str = format!("{}", table);
-      reports.push(table_str);
-    }
-
-    return reports;
+    count_map
   }
 
-  pub fn render(&self) -> Vec<String> {
-    let mut reports = Vec::new();
-
-    let mut count_map = HashMap::new();
+  /// Like [`MessagesDashboard::count_by_code`], grouped by the workspace package of the messages
+  pub fn count_by_package(&self) -> BTreeMap<String, usize> {
+    let mut count_map = BTreeMap::new();
     for msg in self.0 {
       if msg.has_error() {
-        for item in &msg.diagnostics {
-          *count_map
-            .entry(item.code.clone().unwrap_or("unknown".to_string()))
-            .or_insert(0) += 1;
-        }
+        let package = msg.package.as_deref().unwrap_or(ROOT_PACKAGE);
+        *count_map.entry(package.to_string()).or_insert(0) += msg.diagnostics.len();
       }
     }
-    let mut ts = vec![];
+    count_map
+  }
 
-    for (key, value) in count_map {
-      ts.push(Row {
-        name: key,
-        count: value,
-      });
-    }
+  /// Keep only the diagnostics whose code satisfies `predicate`
+  pub fn filter_by_code<F>(&self, predicate: F) -> Vec<Messages>
+  where
+    F: Fn(&DiagnosticCode) -> bool,
+  {
+    self
+      .0
+      .iter()
+      .map(|msg| {
+        let mut msg = msg.clone();
+        msg
+          .diagnostics
+          .retain(|item| predicate(&item.diagnostic_code_or_unknown()));
+        msg
+      })
+      .filter(|msg| !msg.is_empty())
+      .collect()
+  }
+
+  pub fn get_report(&self) -> Vec<String> {
+    let mut reports = Vec::new();
+
+    let ts = self
+      .count_by_code()
+      .into_iter()
+      .map(|(code, count)| Row {
+        name: code.to_string(),
+        count,
+      })
+      .collect::<Vec<_>>();
 
     if !ts.is_empty() {
       let table = Table::new(ts);
       let table_str = format!("{}", table);
       reports.push(table_str);
-      println!("{}", table);
+    }
+
+    // 只有 monorepo 才按包汇总
+    if self.0.iter().any(|msg| msg.package.is_some()) {
+      let ps = self
+        .count_by_package()
+        .into_iter()
+        .map(|(package, count)| PackageRow { package, count })
+        .collect::<Vec<_>>();
+
+      if !ps.is_empty() {
+        reports.push(format!("{}", Table::new(ps)));
+      }
     }
 
     return reports;
   }
+
+  pub fn render(&self) -> Vec<String> {
+    let reports = self.get_report();
+    for report in &reports {
+      println!("{}", report);
+    }
+    reports
+  }
 }
 
 #[cfg(test)]
@@ -91,4 +136,66 @@ mod tests {
     let dashboard = MessagesDashboard::new(&[]);
     dashboard.render();
   }
+
+  #[test]
+  fn should_group_by_code_and_validator() {
+    let diagnostic = |code: &str| {
+      doctor_core::Diagnostic::from(
+        miette::MietteDiagnostic::new("message")
+          .with_code(code)
+          .with_severity(miette::Severity::Error),
+      )
+    };
+    let messages = vec![
+      Messages::builder()
+        .diagnostics(vec![
+          diagnostic("shined(npmrc:invalid-registry)"),
+          diagnostic("shined(npmrc:missing-registry)"),
+          diagnostic("eslint(no-debugger)"),
+        ])
+        .build(),
+    ];
+
+    let dashboard = MessagesDashboard::new(&messages);
+
+    let by_code = dashboard.count_by_code();
+    assert_eq!(
+      by_code.get(&DiagnosticCode::shined("npmrc", "invalid-registry")),
+      Some(&1)
+    );
+
+    let by_group = dashboard.count_by_group();
+    assert_eq!(by_group.get("npmrc"), Some(&2));
+    assert_eq!(by_group.get("eslint"), Some(&1));
+
+    let npmrc = dashboard.filter_by_code(|code| code.group() == "npmrc");
+    assert_eq!(npmrc[0].diagnostics.len(), 2);
+  }
+
+  #[test]
+  fn should_group_by_package() {
+    let diagnostic = || {
+      doctor_core::Diagnostic::from(
+        miette::MietteDiagnostic::new("message")
+          .with_code("eslint(no-debugger)")
+          .with_severity(miette::Severity::Error),
+      )
+    };
+    let messages = vec![
+      Messages::builder()
+        .diagnostics(vec![diagnostic(), diagnostic()])
+        .package("@repo/a")
+        .build(),
+      Messages::builder().diagnostics(vec![diagnostic()]).build(),
+    ];
+
+    let dashboard = MessagesDashboard::new(&messages);
+
+    let by_package = dashboard.count_by_package();
+    assert_eq!(by_package.get("@repo/a"), Some(&2));
+    assert_eq!(by_package.get(ROOT_PACKAGE), Some(&1));
+    assert_eq!(dashboard.get_report().len(), 2);
+
+    assert_eq!(MessagesDashboard::new(&messages[1..]).get_report().len(), 1);
+  }
 }
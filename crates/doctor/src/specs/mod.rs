@@ -1,13 +1,29 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Instant};
 
-use doctor_core::{Messages, ValidatorError};
+use miette::{MietteDiagnostic, Severity};
+use rayon::prelude::*;
+
+use doctor_core::{
+  Baseline, BaselineEntry, ChangedFiles, DOCTOR_VALIDATOR, Diagnostic, DiagnosticCode, Messages,
+  Patch, Policy, SuppressionScope, ValidatorError, ValidatorMeta, ValidatorRegistry, Workspace,
+  apply_suppressions_all, traits::Validator,
+};
 use doctor_lint::Sfconfig;
 
 use crate::MessagesDashboard;
 
+mod outcome;
 mod register;
+mod reporter;
+mod watch;
 mod writer;
 
+pub use outcome::ValidatorOutcome;
+pub use reporter::{
+  CheckstyleReporter, GithubReporter, GitlabReporter, JsonReporter, JunitReporter, ReportFormat,
+  Reporter, SarifReporter,
+};
+pub use watch::{MessagesDelta, WatchDelta, WatchHandle, WatchOpts};
 pub use writer::{ConsoleWriter, StringWriter, Writer};
 
 pub struct SpecificationsRenderOpts {
@@ -24,68 +40,443 @@ impl Default for SpecificationsRenderOpts {
   }
 }
 
+#[derive(Clone)]
 pub struct Specifications {
   cwd: PathBuf,
+  baseline: bool,
+  cache: bool,
+  files: Option<ChangedFiles>,
+  policy: Policy,
 }
 
 impl Specifications {
   pub fn create(cwd: String) -> Specifications {
     let cwd = PathBuf::from(&cwd);
-    Specifications { cwd }
+    Specifications {
+      cwd,
+      baseline: true,
+      cache: true,
+      files: None,
+      policy: Policy::default(),
+    }
+  }
+
+  /// Whether diagnostics in `.sfconfig/baseline.json` are filtered out, enabled by default
+  pub fn with_baseline(mut self, baseline: bool) -> Self {
+    self.baseline = baseline;
+    self
+  }
+
+  /// Whether lint and syntax results of unchanged files are reused, enabled by default
+  pub fn with_cache(mut self, cache: bool) -> Self {
+    self.cache = cache;
+    self
+  }
+
+  /// Only check `files` instead of the whole project, e.g. the result of
+  /// [`ChangedFiles::from_git`]. `ignore` of spec.json still applies, and `.npmrc`,
+  /// `.node-version` and `package.json` are only checked by `validate_all` when they are in it
+  pub fn with_files(mut self, files: ChangedFiles) -> Self {
+    self.files = Some(files);
+    self
+  }
+
+  /// Node versions, registries and package.json rules, fields left unset come from the
+  /// `policy` of spec.json and then from the built-in policy
+  pub fn with_policy(mut self, policy: Policy) -> Self {
+    self.policy = policy;
+    self
+  }
+
+  /// The result cache lives in `cacheDir` of spec.json, `.sfconfig/.cache` when not set
+  fn register_opts(&self, sfconfig: &Sfconfig, workspace: &Workspace) -> register::RegisterOpts {
+    let cache_dir = self.cache.then(|| {
+      let dir = sfconfig
+        .cache_dir
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(".sfconfig").join(".cache"));
+      self.cwd.join(dir)
+    });
+
+    let policy = self
+      .policy
+      .clone()
+      .or(sfconfig.policy.clone())
+      .or(register::builtin_policy());
+
+    let packages = workspace
+      .packages
+      .iter()
+      .map(|package| package.dir.clone())
+      .collect();
+
+    register::RegisterOpts {
+      policy,
+      cache_dir,
+      files: self.files.clone(),
+      packages,
+    }
+  }
+
+  /// The workspace packages under `cwd`, empty when it is not a monorepo
+  ///
+  /// Discovered once per run, the registry and the post processing share the result. When
+  /// discovery fails, e.g. on a malformed `pnpm-workspace.yaml`, the project is checked as a
+  /// single package and the error comes back as a crashed outcome on that file.
+  pub fn workspace(&self) -> (Workspace, Option<ValidatorOutcome>) {
+    match Workspace::discover(&self.cwd) {
+      Ok(workspace) => (workspace, None),
+      Err(error) => {
+        let mut crashed = ValidatorOutcome::crashed(DOCTOR_VALIDATOR, error);
+        let config_path = Workspace::config_path(&self.cwd).display().to_string();
+        for messages in crashed.messages.iter_mut() {
+          messages.source_path = config_path.clone();
+        }
+        (Workspace::default(), Some(crashed))
+      }
+    }
+  }
+
+  pub fn baseline_path(&self) -> PathBuf {
+    self.cwd.join(".sfconfig").join("baseline.json")
+  }
+
+  /// Snapshot the current problems of all validators into the baseline file
+  pub fn update_baseline(&self) -> Result<Baseline, ValidatorError> {
+    let specifications = Specifications {
+      cwd: self.cwd.clone(),
+      baseline: false,
+      cache: self.cache,
+      files: None,
+      policy: self.policy.clone(),
+    };
+    // 部分检查失败时生成的 baseline 是不完整的
+    let mut messages = Vec::new();
+    for outcome in specifications.run_all()? {
+      if let Some(error) = outcome.error {
+        return Err(error);
+      }
+      messages.extend(outcome.messages);
+    }
+    let baseline = Baseline::from_messages(&messages, &self.cwd);
+    baseline.write(self.baseline_path())?;
+    Ok(baseline)
+  }
+
+  fn baseline_fixed(&self, fixed: Vec<BaselineEntry>) -> Messages {
+    let diagnostics = fixed
+      .into_iter()
+      .map(|entry| {
+        Diagnostic::from(
+          MietteDiagnostic::new(format!(
+            "`{}` in {} is fixed but still in the baseline",
+            entry.code, entry.path
+          ))
+          .with_code(DiagnosticCode::shined(DOCTOR_VALIDATOR, "baseline-fixed"))
+          .with_severity(Severity::Advice)
+          .with_help("Update the baseline to lock in the improvement"),
+        )
+      })
+      .collect();
+
+    Messages::builder()
+      .source_path(self.baseline_path().display().to_string())
+      .diagnostics(diagnostics)
+      .validator(DOCTOR_VALIDATOR)
+      .build()
+  }
+
+  fn sfconfig_path(&self) -> PathBuf {
+    self.cwd.join(".sfconfig").join("spec.json")
+  }
+
+  fn sfconfig(&self) -> Result<Sfconfig, ValidatorError> {
+    Sfconfig::parse(self.sfconfig_path())
+  }
+
+  /// spec.json with its `extends` resolved and the effective policy, pretty printed
+  pub fn print_config(&self) -> Result<String, ValidatorError> {
+    let mut sfconfig = self.sfconfig()?;
+    sfconfig.policy = self.register_opts(&sfconfig, &self.workspace().0).policy;
+    serde_json::to_string_pretty(&sfconfig).map_err(|e| ValidatorError::Unknown(Box::new(e)))
+  }
+
+  /// The JSON Schema of `.sfconfig/spec.json`, pretty printed
+  pub fn sfconfig_schema() -> String {
+    serde_json::to_string_pretty(&Sfconfig::json_schema()).unwrap_or_default()
+  }
+
+  /// The validators `validate_all` runs
+  ///
+  /// When spec.json can not be parsed only its own validator runs, so the problems are reported
+  /// as diagnostics instead of one error.
+  pub fn registry(&self) -> Result<ValidatorRegistry, ValidatorError> {
+    self.registry_of(&self.workspace().0)
   }
 
+  fn registry_of(&self, workspace: &Workspace) -> Result<ValidatorRegistry, ValidatorError> {
+    let sfconfig = match self.sfconfig() {
+      Ok(sfconfig) => sfconfig,
+      Err(ValidatorError::IoError(e)) => return Err(ValidatorError::IoError(e)),
+      Err(_) => {
+        let mut registry = ValidatorRegistry::new();
+        registry.register(register::register_sfconfig(self.sfconfig_path()));
+        return Ok(registry);
+      }
+    };
+    let opts = self.register_opts(&sfconfig, workspace);
+    Ok(register::register_all(&self.cwd, sfconfig, &opts))
+  }
+
+  /// Id, description and codes of every available check
+  pub fn list_validators(&self) -> Result<Vec<ValidatorMeta>, ValidatorError> {
+    Ok(self.registry()?.metas())
+  }
+
+  /// Run one validator and apply the steps of [`Specifications::post_process`] to its results,
+  /// `crashed` is the failed workspace discovery of [`Specifications::workspace`]
+  fn validate_with(
+    &self,
+    validator: &dyn Validator,
+    workspace: &Workspace,
+    crashed: Option<ValidatorOutcome>,
+  ) -> Result<Vec<Messages>, ValidatorError> {
+    let messages = validator.validate()?;
+    let mut messages = self.post_process(messages, &validator.meta(), workspace)?;
+    messages.extend(crashed.into_iter().flat_map(|outcome| outcome.messages));
+    Ok(messages)
+  }
+
+  /// Steps applied to the results of one validator
+  fn post_process(
+    &self,
+    mut messages: Vec<Messages>,
+    validator: &ValidatorMeta,
+    workspace: &Workspace,
+  ) -> Result<Vec<Messages>, ValidatorError> {
+    apply_suppressions_all(messages.iter_mut(), SuppressionScope::Only(&[validator]));
+    self.finish(messages, workspace)
+  }
+
+  /// Like [`Specifications::post_process`] for results whose suppressions are applied already
+  ///
+  /// Suppressions must see the results of every validator of a file at once, the remaining
+  /// steps run per validator.
+  fn finish(
+    &self,
+    mut messages: Vec<Messages>,
+    workspace: &Workspace,
+  ) -> Result<Vec<Messages>, ValidatorError> {
+    // spec.json 写坏时由 sfconfig 校验器报告，这里按没有覆盖处理
+    let severity = self
+      .sfconfig()
+      .map(|sfconfig| sfconfig.severity)
+      .unwrap_or_default();
+
+    for messages in messages.iter_mut() {
+      severity.apply(messages);
+      workspace.tag(messages);
+    }
+
+    if self.baseline {
+      if let Some(baseline) = Baseline::read(self.baseline_path())? {
+        let result = baseline.filter(&mut messages, &self.cwd);
+        if !result.fixed.is_empty() {
+          messages.push(self.baseline_fixed(result.fixed));
+        }
+      }
+    }
+
+    Ok(messages)
+  }
+
+  /// Suppressed diagnostics are not fixed
   pub fn fix_lint(&self) -> Result<Vec<Messages>, ValidatorError> {
-    let file = self.cwd.join(".sfconfig").join("spec.json");
-    let sfconfig = Sfconfig::parse(file)?;
-    let lint_builder = register::register_lint(self.cwd.clone(), sfconfig);
-    let message = lint_builder.fix()?;
-    Ok(message)
+    let sfconfig = self.sfconfig()?;
+    let (workspace, crashed) = self.workspace();
+    let opts = self.register_opts(&sfconfig, &workspace);
+    let lint_builder = register::register_lint(self.cwd.clone(), sfconfig, &opts);
+    let mut messages = lint_builder.fixes()?;
+    apply_suppressions_all(
+      messages.iter_mut(),
+      SuppressionScope::Only(&[&lint_builder.meta()]),
+    );
+    for messages in &messages {
+      messages.write_fixes()?;
+    }
+    let mut messages = self.finish(messages, &workspace)?;
+    messages.extend(crashed.into_iter().flat_map(|outcome| outcome.messages));
+    Ok(messages)
+  }
+
+  /// Like [`Specifications::fix_lint`], but returns the changes as a patch relative to `cwd`
+  /// instead of writing them, the output can be applied with `git apply`
+  pub fn fix_lint_dry_run(&self) -> Result<Patch, ValidatorError> {
+    let sfconfig = self.sfconfig()?;
+    let opts = self.register_opts(&sfconfig, &self.workspace().0);
+    let lint_builder = register::register_lint(self.cwd.clone(), sfconfig, &opts);
+    let mut messages = lint_builder.fixes()?;
+    apply_suppressions_all(
+      messages.iter_mut(),
+      SuppressionScope::Only(&[&lint_builder.meta()]),
+    );
+    Ok(Patch::from_messages(&messages).strip_prefix(&self.cwd))
+  }
+
+  /// The fixes of every fixable validator in the registry, suppressed diagnostics left out
+  ///
+  /// A validator that fails here is skipped, the run after fixing reports it as crashed.
+  fn fixes_all(&self) -> Result<Vec<Messages>, ValidatorError> {
+    let registry = self.registry()?;
+    let mut fixed = Vec::new();
+    let mut messages = Vec::new();
+    for (meta, validator) in registry.entries().filter(|(meta, _)| meta.fixable) {
+      if let Ok(fixes) = validator.fixes() {
+        fixed.push(meta);
+        messages.extend(fixes);
+      }
+    }
+    apply_suppressions_all(messages.iter_mut(), SuppressionScope::Only(&fixed));
+    Ok(messages)
+  }
+
+  /// Apply the fixes of every fixable validator, e.g. lint, `.npmrc` and package.json, then run
+  /// every validator again so the outcomes show what is left
+  pub fn fix_all(&self) -> Result<Vec<ValidatorOutcome>, ValidatorError> {
+    for messages in self.fixes_all()? {
+      messages.write_fixes()?;
+    }
+    self.run_all()
+  }
+
+  /// Like [`Specifications::fix_all`], but returns the changes as a patch relative to `cwd`
+  /// instead of writing them
+  pub fn fix_all_dry_run(&self) -> Result<Patch, ValidatorError> {
+    let messages = self.fixes_all()?;
+    Ok(Patch::from_messages(&messages).strip_prefix(&self.cwd))
+  }
+
+  pub fn validate_sfconfig(&self) -> Result<Vec<Messages>, ValidatorError> {
+    let (workspace, crashed) = self.workspace();
+    let sfconfig_builder = register::register_sfconfig(self.sfconfig_path());
+    self.validate_with(sfconfig_builder.as_ref(), &workspace, crashed)
   }
 
   pub fn validate_npmrc(&self) -> Result<Vec<Messages>, ValidatorError> {
     let file = self.cwd.join(".npmrc");
-    let npmrc_builder = register::register_npmrc(file);
-    let message = npmrc_builder.validate()?;
-    Ok(message)
+    let (workspace, crashed) = self.workspace();
+    let opts = self.register_opts(&self.sfconfig()?, &workspace);
+    let npmrc_builder = register::register_npmrc(file, &opts.policy);
+    self.validate_with(npmrc_builder.as_ref(), &workspace, crashed)
   }
 
   pub fn validate_node_version(&self) -> Result<Vec<Messages>, ValidatorError> {
     let file = self.cwd.join(".node-version");
-    let node_version_builder = register::register_node_version(file);
-    let message = node_version_builder.validate()?;
-    Ok(message)
+    let (workspace, crashed) = self.workspace();
+    let opts = self.register_opts(&self.sfconfig()?, &workspace);
+    let node_version_builder = register::register_node_version(file, &opts.policy);
+    self.validate_with(node_version_builder.as_ref(), &workspace, crashed)
   }
 
   pub fn validate_package_json(&self) -> Result<Vec<Messages>, ValidatorError> {
-    let file = self.cwd.join("package.json");
-    let package_json_builder = register::register_package_json(file);
-    let message = package_json_builder.validate()?;
-    Ok(message)
+    let (workspace, crashed) = self.workspace();
+    let opts = self.register_opts(&self.sfconfig()?, &workspace);
+    let package_json_builder = register::register_workspace_package_json(&self.cwd, &opts);
+    self.validate_with(package_json_builder.as_ref(), &workspace, crashed)
   }
 
   pub fn validate_lint(&self) -> Result<Vec<Messages>, ValidatorError> {
-    let file = self.cwd.join(".sfconfig").join("spec.json");
-    let sfconfig = Sfconfig::parse(file)?;
-    let lint_builder = register::register_lint(self.cwd.clone(), sfconfig);
-    let message = lint_builder.validate()?;
-    Ok(message)
+    let sfconfig = self.sfconfig()?;
+    let (workspace, crashed) = self.workspace();
+    let opts = self.register_opts(&sfconfig, &workspace);
+    let lint_builder = register::register_lint(self.cwd.clone(), sfconfig, &opts);
+    self.validate_with(lint_builder.as_ref(), &workspace, crashed)
   }
 
   pub fn validate_syntax(&self) -> Result<Vec<Messages>, ValidatorError> {
-    let file = self.cwd.join(".sfconfig").join("spec.json");
-    let sfconfig = Sfconfig::parse(file)?;
-    let syntax_builder = register::register_syntax(self.cwd.clone(), sfconfig);
-    let message = syntax_builder.validate()?;
-    Ok(message)
+    let sfconfig = self.sfconfig()?;
+    let (workspace, crashed) = self.workspace();
+    let opts = self.register_opts(&sfconfig, &workspace);
+    let syntax_builder = register::register_syntax(self.cwd.clone(), sfconfig, &opts);
+    self.validate_with(syntax_builder.as_ref(), &workspace, crashed)
   }
 
+  /// Run every registered validator in parallel, a failing one does not stop the others
+  ///
+  /// Outcomes keep the registration order whichever validator finishes first.
+  ///
+  /// Lint and syntax share a [`doctor_lint::SourcePipeline`], it is resolved through
+  /// [`Validator::prepare`] before the fan-out so no rayon worker blocks on it.
+  pub fn run_all(&self) -> Result<Vec<ValidatorOutcome>, ValidatorError> {
+    let (workspace, crashed) = self.workspace();
+    let registry = self.registry_of(&workspace)?;
+    let mut outcomes = self.run_validators(registry.entries().collect(), true, &workspace);
+    outcomes.extend(crashed);
+    Ok(outcomes)
+  }
+
+  /// See [`Specifications::run_all`], `complete` tells whether `validators` is the whole registry
+  fn run_validators(
+    &self,
+    validators: Vec<(&ValidatorMeta, &(dyn Validator + Send + Sync))>,
+    complete: bool,
+    workspace: &Workspace,
+  ) -> Vec<ValidatorOutcome> {
+    // 共享的解析在这里完成，耗时记到对应的校验器上
+    let prepared = validators
+      .iter()
+      .map(|(_, validator)| {
+        let start = Instant::now();
+        let _ = validator.prepare();
+        start.elapsed()
+      })
+      .collect::<Vec<_>>();
+
+    let mut outcomes = validators
+      .par_iter()
+      .zip(prepared)
+      .map(|(&(meta, validator), prepared)| {
+        let mut outcome = ValidatorOutcome::run(meta, validator, Ok);
+        outcome.duration += prepared;
+        outcome
+      })
+      .collect::<Vec<_>>();
+
+    // 崩溃的校验器没有结果，它的指令不能算作没用到
+    let ran = validators
+      .iter()
+      .zip(&outcomes)
+      .filter(|(_, outcome)| !outcome.is_crashed())
+      .map(|((meta, _), _)| *meta)
+      .collect::<Vec<_>>();
+    let scope = if complete && ran.len() == validators.len() {
+      SuppressionScope::All
+    } else {
+      SuppressionScope::Only(&ran)
+    };
+
+    // lint 和 syntax 会报告同一个文件，指令要合并后才知道有没有用到
+    apply_suppressions_all(
+      outcomes
+        .iter_mut()
+        .filter(|outcome| !outcome.is_crashed())
+        .flat_map(|outcome| outcome.messages.iter_mut()),
+      scope,
+    );
+
+    outcomes
+      .into_iter()
+      .map(|outcome| outcome.and_then(|messages| self.finish(messages, workspace)))
+      .collect()
+  }
+
+  /// Messages of every validator, crashed ones report `shined(doctor:validator-crashed)`
   pub fn validate_all(&self) -> Result<Vec<Messages>, ValidatorError> {
-    let mut messages = Vec::new();
-    messages.extend(self.validate_npmrc()?);
-    messages.extend(self.validate_node_version()?);
-    messages.extend(self.validate_package_json()?);
-    messages.extend(self.validate_lint()?);
-    messages.extend(self.validate_syntax()?);
+    let messages = self
+      .run_all()?
+      .into_iter()
+      .flat_map(|outcome| outcome.messages)
+      .collect();
     Ok(messages)
   }
 
@@ -139,6 +530,16 @@ impl Specifications {
     }
   }
 
+  /// Write `messages` in a machine readable `format`, paths are relative to `cwd`
+  pub fn report_with_writer(
+    &self,
+    messages: &[Messages],
+    format: ReportFormat,
+    writer: &mut impl Writer,
+  ) {
+    format.report(&self.cwd, messages, writer);
+  }
+
   pub fn render(&self, messages: &Vec<Messages>, opts: SpecificationsRenderOpts) -> Vec<String> {
     let _ = miette::set_hook(Box::new(|_| {
       Box::new(
@@ -186,3 +587,167 @@ impl Specifications {
     return reports;
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::{testing::project, traits::DiagnosticCodeExt};
+
+  use super::*;
+
+  #[test]
+  fn should_apply_suppressions_across_lint_and_syntax() {
+    let dir = project(
+      "specs-suppress",
+      &[(
+        "a.js",
+        "// doctor-disable-next-line eslint(no-compare-neg-zero)\nexport const isNegZero = (x) => x === -0;\n",
+      )],
+    );
+    let file = dir.join("a.js");
+
+    let specifications = Specifications::create(dir.display().to_string())
+      .with_baseline(false)
+      .with_cache(false);
+    let outcomes = specifications.run_all().unwrap();
+
+    let ran = outcomes
+      .iter()
+      .map(|outcome| outcome.validator.as_str())
+      .collect::<Vec<_>>();
+    assert!(ran.contains(&"lint") && ran.contains(&"syntax"));
+
+    let codes = outcomes
+      .iter()
+      .flat_map(|outcome| &outcome.messages)
+      .filter(|messages| std::path::Path::new(&messages.source_path) == file)
+      .flat_map(|messages| messages.iter())
+      .map(|diagnostic| diagnostic.diagnostic_code_or_unknown().to_string())
+      .collect::<Vec<_>>();
+    assert!(codes.is_empty(), "{codes:?}");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn should_not_report_directives_of_validators_that_did_not_run() {
+    let dir = project(
+      "specs-suppress-scope",
+      &[(
+        "a.js",
+        "// doctor-disable-next-line eslint(no-debugger)\ndebugger;\n",
+      )],
+    );
+
+    let specifications = Specifications::create(dir.display().to_string())
+      .with_baseline(false)
+      .with_cache(false);
+    let messages = specifications.validate_syntax().unwrap();
+
+    assert!(messages.iter().all(|messages| messages.is_empty()));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn should_fix_every_fixable_validator() {
+    let dir = project(
+      "specs-fix-all",
+      &[(".npmrc", "registry=https://example.com/\n")],
+    );
+
+    let specifications = Specifications::create(dir.display().to_string())
+      .with_baseline(false)
+      .with_cache(false);
+    let patch = specifications.fix_all_dry_run().unwrap();
+    assert!(
+      patch
+        .0
+        .iter()
+        .any(|file| file.path == std::path::Path::new(".npmrc"))
+    );
+
+    let outcomes = specifications.fix_all().unwrap();
+    let npmrc = outcomes
+      .iter()
+      .find(|outcome| outcome.validator == doctor_npmrc::diagnostics::VALIDATOR)
+      .unwrap();
+    assert!(npmrc.messages.iter().all(|messages| messages.is_empty()));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn should_report_malformed_workspace_and_check_the_rest() {
+    let dir = project(
+      "specs-workspace",
+      &[
+        ("pnpm-workspace.yaml", "packages: ["),
+        (".npmrc", "registry=https://example.com/\n"),
+      ],
+    );
+
+    let specifications = Specifications::create(dir.display().to_string())
+      .with_baseline(false)
+      .with_cache(false);
+    let outcomes = specifications.run_all().unwrap();
+
+    let crashed = outcomes
+      .iter()
+      .filter(|outcome| outcome.is_crashed())
+      .collect::<Vec<_>>();
+    assert_eq!(crashed.len(), 1);
+    assert_eq!(crashed[0].validator, DOCTOR_VALIDATOR);
+    assert_eq!(
+      crashed[0].messages[0].source_path,
+      dir.join("pnpm-workspace.yaml").display().to_string()
+    );
+    assert!(
+      outcomes
+        .iter()
+        .any(|outcome| outcome.validator == doctor_npmrc::diagnostics::VALIDATOR)
+    );
+
+    let messages = specifications.validate_npmrc().unwrap();
+    assert!(
+      messages
+        .iter()
+        .any(|messages| messages.validator.as_deref() == Some(DOCTOR_VALIDATOR))
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn should_check_other_packages_when_one_can_not_be_read() {
+    let dir = project(
+      "specs-packages",
+      &[
+        ("package.json", r#"{ "workspaces": ["packages/*"] }"#),
+        ("packages/a/package.json", "{}"),
+        ("packages/b/package.json", ""),
+      ],
+    );
+    // 不是 UTF-8，读取就会失败
+    std::fs::write(dir.join("packages/b/package.json"), [0xff, 0xfe]).unwrap();
+
+    let specifications = Specifications::create(dir.display().to_string()).with_baseline(false);
+    let messages = specifications.validate_package_json().unwrap();
+
+    let crashed = messages
+      .iter()
+      .filter(|messages| messages.validator.as_deref() == Some(DOCTOR_VALIDATOR))
+      .map(|messages| messages.source_path.as_str())
+      .collect::<Vec<_>>();
+    let package_b = dir.join("packages/b/package.json").display().to_string();
+    assert_eq!(crashed, vec![package_b.as_str()]);
+
+    let package_a = dir.join("packages/a/package.json").display().to_string();
+    assert!(
+      messages
+        .iter()
+        .any(|messages| messages.source_path == package_a && !messages.is_empty())
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
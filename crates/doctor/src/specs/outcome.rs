@@ -0,0 +1,123 @@
+use std::time::{Duration, Instant};
+
+use doctor_core::{Messages, ValidatorError, ValidatorMeta, traits::Validator, validator_crashed};
+
+/// The result of one validator in [`super::Specifications::run_all`]
+#[derive(Debug)]
+pub struct ValidatorOutcome {
+  pub validator: String,
+  /// The reported messages, a `shined(doctor:validator-crashed)` diagnostic when it failed
+  pub messages: Vec<Messages>,
+  pub error: Option<ValidatorError>,
+  /// Wall time of the run, including severity overrides and baseline filtering
+  pub duration: Duration,
+}
+
+impl ValidatorOutcome {
+  /// Run `validator` registered with `meta`, turning an error into a crashed outcome
+  ///
+  /// Panics are not caught, the release profile aborts on panic anyway.
+  pub(crate) fn run(
+    meta: &ValidatorMeta,
+    validator: &dyn Validator,
+    post_process: impl FnOnce(Vec<Messages>) -> Result<Vec<Messages>, ValidatorError>,
+  ) -> Self {
+    let id = meta.id.clone();
+    let start = Instant::now();
+
+    let result = validator.validate().and_then(post_process);
+
+    let duration = start.elapsed();
+
+    match result {
+      Ok(messages) => Self {
+        validator: id,
+        messages,
+        error: None,
+        duration,
+      },
+      Err(error) => Self {
+        duration,
+        ..Self::crashed(id, error)
+      },
+    }
+  }
+
+  /// Apply `f` to the messages of a successful outcome, an error turns it into a crashed one
+  pub(crate) fn and_then(
+    self,
+    f: impl FnOnce(Vec<Messages>) -> Result<Vec<Messages>, ValidatorError>,
+  ) -> Self {
+    if self.is_crashed() {
+      return self;
+    }
+
+    let start = Instant::now();
+    let result = f(self.messages);
+    let duration = self.duration + start.elapsed();
+
+    match result {
+      Ok(messages) => Self {
+        messages,
+        duration,
+        ..self
+      },
+      Err(error) => Self {
+        duration,
+        ..Self::crashed(self.validator, error)
+      },
+    }
+  }
+
+  /// An outcome for a validator that failed before it could run
+  pub(crate) fn crashed(validator: impl Into<String>, error: ValidatorError) -> Self {
+    let validator = validator.into();
+    Self {
+      messages: vec![validator_crashed(&validator, &error)],
+      validator,
+      error: Some(error),
+      duration: Duration::ZERO,
+    }
+  }
+
+  pub fn is_crashed(&self) -> bool {
+    self.error.is_some()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct Broken;
+
+  impl Validator for Broken {
+    fn meta(&self) -> ValidatorMeta {
+      ValidatorMeta::builder()
+        .id("broken")
+        .description("Always fails")
+        .build()
+    }
+
+    fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+      Err(std::io::Error::other("permission denied").into())
+    }
+
+    fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
+      self.validate()
+    }
+  }
+
+  #[test]
+  fn should_turn_error_into_crashed_diagnostic() {
+    let outcome = ValidatorOutcome::run(&Broken.meta(), &Broken, Ok);
+
+    assert!(outcome.is_crashed());
+    assert_eq!(outcome.validator, "broken");
+    assert_eq!(
+      outcome.messages[0][0].code.as_deref(),
+      Some("shined(doctor:validator-crashed)")
+    );
+    assert!(outcome.messages[0][0].message.contains("permission denied"));
+  }
+}
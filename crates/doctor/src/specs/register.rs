@@ -1,10 +1,16 @@
-use std::path::Path;
+use std::{
+  path::{Path, PathBuf},
+  sync::Arc,
+};
 
 use base64::{Engine, engine::general_purpose::STANDARD};
-use doctor_core::traits::Validator;
+use doctor_core::{
+  ChangedFiles, Messages, PackageJsonPolicy, Policy, PrivatePolicy, ValidatorError, ValidatorMeta,
+  ValidatorRegistry, traits::Validator, validator_crashed,
+};
 use doctor_lint::{
   Category, EnvironmentFlags, LintMode, LintValidator, OxlintrcBuilder, Sfconfig,
-  inner::Category20250601Inner,
+  SfconfigValidator, SourcePipeline, inner::Category20250601Inner,
 };
 use doctor_node::validator::NodeVersionValidator;
 use doctor_npmrc::validator::NpmrcValidator;
@@ -23,12 +29,59 @@ fn decode_to_str(encoded: &str) -> String {
   String::from_utf8(decoded).unwrap()
 }
 
-pub fn register_lint(cwd: impl AsRef<Path>, sfconfig: Sfconfig) -> Box<dyn Validator> {
+/// The policy before this became configurable, fills in whatever is not set elsewhere
+pub fn builtin_policy() -> Policy {
+  Policy {
+    node_versions: Some(
+      ["^20.9.0", "^22.11.0", "^24.10.0"]
+        .map(String::from)
+        .to_vec(),
+    ),
+    registries: Some(vec![decode_to_str(ENCODED.join("").as_str())]),
+    package_json: Some(PackageJsonPolicy {
+      name: Some(true),
+      private: Some(PrivatePolicy::True),
+      package_manager: Some(true),
+      shineout_version: Some(true),
+    }),
+  }
+}
+
+/// An empty list turns the check off, the validators take that as `None`
+fn non_empty(list: &Option<Vec<String>>) -> Option<Vec<String>> {
+  list.clone().filter(|list| !list.is_empty())
+}
+
+/// Options of the registered validators
+#[derive(Debug, Clone, Default)]
+pub struct RegisterOpts {
+  /// Fully resolved, see [`builtin_policy`]
+  pub policy: Policy,
+  /// See [`LintValidator`], `None` disables the result cache
+  pub cache_dir: Option<PathBuf>,
+  /// Only check these files, `None` checks the whole tree
+  pub files: Option<ChangedFiles>,
+  /// Directories of the workspace packages, see [`doctor_core::Workspace`]
+  pub packages: Vec<PathBuf>,
+}
+
+impl RegisterOpts {
+  /// Config files are only checked when nothing narrows the run or they changed
+  fn should_check(&self, config: &Path) -> bool {
+    self
+      .files
+      .as_ref()
+      .is_none_or(|files| files.contains(config))
+  }
+}
+
+fn lint_validator(cwd: impl AsRef<Path>, sfconfig: Sfconfig, opts: &RegisterOpts) -> LintValidator {
   let category = Category20250601Inner::builder()
     .globals(sfconfig.globals)
     .mode(LintMode::Production)
     .envs(EnvironmentFlags::default())
     .ignore(sfconfig.ignore.clone())
+    .severity(sfconfig.severity.clone())
     .build();
 
   let category = Category::V20250601Inner(category);
@@ -40,46 +93,215 @@ pub fn register_lint(cwd: impl AsRef<Path>, sfconfig: Sfconfig) -> Box<dyn Valid
     .ignore(sfconfig.ignore.clone())
     .with_show_report(false)
     .oxlintrc(rc)
+    .cache_dir(opts.cache_dir.clone())
+    .files(opts.files.clone())
     .build();
 
-  Box::new(validator)
+  validator
 }
 
-pub fn register_node_version(cwd: impl AsRef<Path>) -> Box<dyn Validator> {
+pub fn register_lint(
+  cwd: impl AsRef<Path>,
+  sfconfig: Sfconfig,
+  opts: &RegisterOpts,
+) -> Box<dyn Validator + Send + Sync> {
+  Box::new(lint_validator(cwd, sfconfig, opts))
+}
+
+pub fn register_node_version(
+  cwd: impl AsRef<Path>,
+  policy: &Policy,
+) -> Box<dyn Validator + Send + Sync> {
   let validator = NodeVersionValidator::builder()
     .config_path(cwd.as_ref().to_path_buf())
-    .with_valid_range(vec!["^20.9.0", "^22.11.0", "^24.10.0"])
+    .with_valid_range_opt(non_empty(&policy.node_versions))
     .build();
 
   Box::new(validator)
 }
 
-pub fn register_npmrc(cwd: impl AsRef<Path>) -> Box<dyn Validator> {
-  let text = decode_to_str(ENCODED.join("").as_str());
+pub fn register_npmrc(cwd: impl AsRef<Path>, policy: &Policy) -> Box<dyn Validator + Send + Sync> {
   let validator = NpmrcValidator::builder()
     .config_path(cwd.as_ref().to_path_buf())
-    .with_registry_url(vec![text])
+    .with_registry_url_opt(non_empty(&policy.registries))
+    .build();
+
+  Box::new(validator)
+}
+
+pub fn register_sfconfig(cwd: impl AsRef<Path>) -> Box<dyn Validator + Send + Sync> {
+  let validator = SfconfigValidator::builder()
+    .config_path(cwd.as_ref().to_path_buf())
     .build();
 
   Box::new(validator)
 }
 
-pub fn register_package_json(cwd: impl AsRef<Path>) -> Box<dyn Validator> {
+pub fn register_package_json(
+  cwd: impl AsRef<Path>,
+  policy: &Policy,
+) -> Box<dyn Validator + Send + Sync> {
+  let policy = policy.package_json.clone().unwrap_or_default();
+  let private = match policy.private {
+    Some(PrivatePolicy::Exist) => Some(ValidatePrivate::Exist),
+    Some(PrivatePolicy::True) => Some(ValidatePrivate::True),
+    Some(PrivatePolicy::False) => Some(ValidatePrivate::False),
+    Some(PrivatePolicy::Off) | None => None,
+  };
+
   let validator = PackageJsonValidator::builder()
     .config_path(cwd.as_ref().to_path_buf())
-    .with_validate_name(ValidateName::Exist)
-    .with_validate_private(ValidatePrivate::True)
-    .with_validate_package_manager(ValidatePackageManager::Exist)
-    .with_validate_shineout_version(true)
+    .with_validate_name_opt(policy.name.unwrap_or(false).then_some(ValidateName::Exist))
+    .with_validate_private_opt(private)
+    .with_validate_package_manager_opt(
+      policy
+        .package_manager
+        .unwrap_or(false)
+        .then_some(ValidatePackageManager::Exist),
+    )
+    // 校验器只看是否为 Some，关闭时要传 None
+    .with_validate_shineout_version_opt(policy.shineout_version.filter(|enabled| *enabled))
     .build();
 
   Box::new(validator)
 }
 
-pub fn register_syntax(cwd: impl AsRef<Path>, sfconfig: Sfconfig) -> Box<dyn Validator> {
+/// The same check run on the config file of several workspace packages, under one id
+///
+/// A package whose file can not be checked reports `shined(doctor:validator-crashed)` for that
+/// file, the other packages are still checked.
+struct EachPackage {
+  meta: ValidatorMeta,
+  /// The config file of each package and its validator
+  validators: Vec<(PathBuf, Box<dyn Validator + Send + Sync>)>,
+}
+
+impl EachPackage {
+  fn each(
+    &self,
+    run: impl Fn(&(dyn Validator + Send + Sync)) -> Result<Vec<Messages>, ValidatorError>,
+  ) -> Vec<Messages> {
+    let mut messages = vec![];
+    for (file, validator) in &self.validators {
+      match run(validator.as_ref()) {
+        Ok(result) => messages.extend(result),
+        Err(error) => {
+          let mut crashed = validator_crashed(&self.meta.id, &error);
+          crashed.source_path = file.display().to_string();
+          messages.push(crashed);
+        }
+      }
+    }
+    messages
+  }
+}
+
+impl Validator for EachPackage {
+  fn meta(&self) -> ValidatorMeta {
+    self.meta.clone()
+  }
+
+  fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+    Ok(self.each(|validator| validator.validate()))
+  }
+
+  fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
+    Ok(self.each(|validator| validator.fix()))
+  }
+
+  fn fixes(&self) -> Result<Vec<Messages>, ValidatorError> {
+    Ok(self.each(|validator| validator.fixes()))
+  }
+}
+
+/// The package.json of the root and of every workspace package in `opts.packages`
+///
+/// `packageManager` is only checked at the root, the rest of the policy applies everywhere.
+/// With `opts.files` set, only the package.json files in it are checked.
+pub fn register_workspace_package_json(
+  cwd: impl AsRef<Path>,
+  opts: &RegisterOpts,
+) -> Box<dyn Validator + Send + Sync> {
+  let root = cwd.as_ref().join("package.json");
+  let root_validator = register_package_json(&root, &opts.policy);
+  let meta = root_validator.meta();
+
+  let mut validators = vec![];
+  if opts.should_check(&root) {
+    validators.push((root, root_validator));
+  }
+
+  let mut policy = opts.policy.clone();
+  if let Some(package_json) = policy.package_json.as_mut() {
+    package_json.package_manager = Some(false);
+  }
+  for dir in &opts.packages {
+    let file = dir.join("package.json");
+    if opts.should_check(&file) {
+      let validator = register_package_json(&file, &policy);
+      validators.push((file, validator));
+    }
+  }
+
+  Box::new(EachPackage { meta, validators })
+}
+
+/// Every validator `validate_all` runs, in report order
+///
+/// Lint and syntax share one [`SourcePipeline`], so each file is read and parsed once.
+/// With `opts.files` set, a config file validator is only registered when its file is in it.
+/// `.npmrc` and `.node-version` are root only, package.json is checked in every package.
+pub fn register_all(
+  cwd: impl AsRef<Path>,
+  sfconfig: Sfconfig,
+  opts: &RegisterOpts,
+) -> ValidatorRegistry {
+  let cwd = cwd.as_ref();
+  let pipeline = SourcePipeline::new(lint_validator(cwd, sfconfig.clone(), opts));
+  let syntax = SyntaxValidator::builder()
+    .cwd(cwd.to_path_buf())
+    .ignore(sfconfig.ignore)
+    .source(Arc::new(pipeline.clone()))
+    .files(opts.files.clone())
+    .build();
+
+  let mut registry = ValidatorRegistry::new();
+
+  let spec = cwd.join(".sfconfig").join("spec.json");
+  if opts.should_check(&spec) {
+    registry.register(register_sfconfig(spec));
+  }
+  let npmrc = cwd.join(".npmrc");
+  if opts.should_check(&npmrc) {
+    registry.register(register_npmrc(npmrc, &opts.policy));
+  }
+  let node_version = cwd.join(".node-version");
+  if opts.should_check(&node_version) {
+    registry.register(register_node_version(node_version, &opts.policy));
+  }
+  let package_json = cwd.join("package.json");
+  let any_package_json = std::iter::once(package_json)
+    .chain(opts.packages.iter().map(|dir| dir.join("package.json")))
+    .any(|file| opts.should_check(&file));
+  if any_package_json {
+    registry.register(register_workspace_package_json(cwd, opts));
+  }
+
+  registry
+    .register(Box::new(pipeline))
+    .register(Box::new(syntax));
+  registry
+}
+
+pub fn register_syntax(
+  cwd: impl AsRef<Path>,
+  sfconfig: Sfconfig,
+  opts: &RegisterOpts,
+) -> Box<dyn Validator + Send + Sync> {
   let validator = SyntaxValidator::builder()
     .cwd(cwd.as_ref().to_path_buf())
     .ignore(sfconfig.ignore)
+    .files(opts.files.clone())
     .build();
   Box::new(validator)
 }
@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+
+use doctor_core::{JsonSeverity, Messages, traits::DiagnosticCodeExt};
+
+use super::{Reporter, xml::escape};
+use crate::specs::Writer;
+
+/// Writes a Checkstyle XML report, files without diagnostics are left out
+pub struct CheckstyleReporter {
+  cwd: PathBuf,
+}
+
+impl CheckstyleReporter {
+  pub fn new(cwd: impl AsRef<Path>) -> Self {
+    Self {
+      cwd: cwd.as_ref().to_path_buf(),
+    }
+  }
+
+  pub fn to_checkstyle(&self, messages: &[Messages]) -> String {
+    let mut xml = vec![
+      r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+      r#"<checkstyle version="4.3">"#.to_string(),
+    ];
+
+    for messages in messages.iter().filter(|m| !m.is_empty()) {
+      let path = messages.relative_path(&self.cwd);
+      xml.push(format!(r#"  <file name="{}">"#, escape(&path)));
+
+      let index = messages.line_index();
+
+      for diagnostic in messages.iter() {
+        let (line, column) = diagnostic
+          .primary_location(&index)
+          .map_or((1, 1), |location| (location.start.row, location.start.col));
+
+        let severity = match JsonSeverity::from(diagnostic.severity) {
+          JsonSeverity::Error => "error",
+          JsonSeverity::Warning => "warning",
+          JsonSeverity::Advice => "info",
+        };
+
+        xml.push(format!(
+          r#"    <error line="{}" column="{}" severity="{}" message="{}" source="{}" />"#,
+          line,
+          column,
+          severity,
+          escape(&diagnostic.message),
+          escape(&diagnostic.diagnostic_code_or_unknown().to_string()),
+        ));
+      }
+
+      xml.push("  </file>".to_string());
+    }
+
+    xml.push("</checkstyle>".to_string());
+    xml.join("\n")
+  }
+}
+
+impl Reporter for CheckstyleReporter {
+  fn report(&self, messages: &[Messages], writer: &mut impl Writer) {
+    writer.write(self.to_checkstyle(messages));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::Diagnostic;
+  use miette::{LabeledSpan, MietteDiagnostic, Severity};
+
+  use super::*;
+
+  #[test]
+  fn should_write_errors_per_file() {
+    let messages = Messages::builder()
+      .source_code("let a = 1;\nif (a) debugger;\n".to_string())
+      .source_path("/repo/src/a.js".to_string())
+      .diagnostics(vec![Diagnostic::from(
+        MietteDiagnostic::new("`debugger` statement is not allowed")
+          .with_code("eslint(no-debugger)")
+          .with_severity(Severity::Warning)
+          .with_label(LabeledSpan::at(18..26, "")),
+      )])
+      .build();
+    let clean = Messages::builder()
+      .source_path("/repo/src/b.js".to_string())
+      .build();
+
+    let xml = CheckstyleReporter::new("/repo").to_checkstyle(&[messages, clean]);
+
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="src/a.js">
+    <error line="2" column="8" severity="warning" message="`debugger` statement is not allowed" source="eslint(no-debugger)" />
+  </file>
+</checkstyle>"#
+    );
+  }
+}
@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use doctor_core::{JsonSeverity, Messages, traits::DiagnosticCodeExt};
+
+use super::Reporter;
+use crate::specs::Writer;
+
+/// Writes GitHub Actions workflow commands, e.g.
+/// `::error file=src/a.js,line=1,col=1,endLine=1,endColumn=9,title=eslint(no-debugger)::message`
+///
+/// Diagnostics without labels are annotated on the whole file.
+pub struct GithubReporter {
+  cwd: PathBuf,
+}
+
+impl GithubReporter {
+  pub fn new(cwd: impl AsRef<Path>) -> Self {
+    Self {
+      cwd: cwd.as_ref().to_path_buf(),
+    }
+  }
+
+  /// Escape the message part of a workflow command
+  fn escape_data(value: &str) -> String {
+    value
+      .replace('%', "%25")
+      .replace('\r', "%0D")
+      .replace('\n', "%0A")
+  }
+
+  /// Escape a property value of a workflow command
+  fn escape_property(value: &str) -> String {
+    Self::escape_data(value)
+      .replace(':', "%3A")
+      .replace(',', "%2C")
+  }
+
+  pub fn to_annotations(&self, messages: &[Messages]) -> Vec<String> {
+    let mut annotations = Vec::new();
+
+    for messages in messages {
+      let path = messages.relative_path(&self.cwd);
+      let index = messages.line_index();
+
+      for diagnostic in messages.iter() {
+        let command = match JsonSeverity::from(diagnostic.severity) {
+          JsonSeverity::Error => "error",
+          JsonSeverity::Warning => "warning",
+          JsonSeverity::Advice => "notice",
+        };
+
+        let mut properties = Vec::new();
+
+        if !path.is_empty() {
+          properties.push(format!("file={}", Self::escape_property(&path)));
+        }
+
+        if let Some(location) = diagnostic.primary_location(&index) {
+          properties.push(format!("line={}", location.start.row));
+          properties.push(format!("col={}", location.start.col));
+          properties.push(format!("endLine={}", location.end.row));
+          properties.push(format!("endColumn={}", location.end.col));
+        }
+
+        let code = diagnostic.diagnostic_code_or_unknown().to_string();
+        properties.push(format!("title={}", Self::escape_property(&code)));
+
+        let mut message = diagnostic.message.clone();
+        if let Some(help) = &diagnostic.help {
+          message.push_str("\n\n");
+          message.push_str(help);
+        }
+
+        annotations.push(format!(
+          "::{} {}::{}",
+          command,
+          properties.join(","),
+          Self::escape_data(&message)
+        ));
+      }
+    }
+
+    annotations
+  }
+}
+
+impl Reporter for GithubReporter {
+  fn report(&self, messages: &[Messages], writer: &mut impl Writer) {
+    for annotation in self.to_annotations(messages) {
+      writer.write(annotation);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::Diagnostic;
+  use miette::{LabeledSpan, MietteDiagnostic, Severity};
+
+  use super::*;
+
+  #[test]
+  fn should_annotate_label_span() {
+    let messages = Messages::builder()
+      .source_code("let a = 1;\nif (a) debugger;\n".to_string())
+      .source_path("/repo/src/a.js".to_string())
+      .diagnostics(vec![Diagnostic::from(
+        MietteDiagnostic::new("`debugger` statement is not allowed")
+          .with_code("eslint(no-debugger)")
+          .with_severity(Severity::Warning)
+          .with_label(LabeledSpan::at(18..26, "")),
+      )])
+      .build();
+
+    let annotations = GithubReporter::new("/repo").to_annotations(&[messages]);
+
+    assert_eq!(
+      annotations,
+      vec![
+        "::warning file=src/a.js,line=2,col=8,endLine=2,endColumn=16,title=eslint(no-debugger)::`debugger` statement is not allowed"
+      ]
+    );
+  }
+
+  #[test]
+  fn should_fall_back_to_file_annotation() {
+    let messages = Messages::builder()
+      .source_path("/repo/.npmrc".to_string())
+      .diagnostics(vec![Diagnostic::from(
+        MietteDiagnostic::new("Config file not found: 100%")
+          .with_code("shined(npmrc:config-file-not-found)")
+          .with_help("Create .npmrc\nwith registry"),
+      )])
+      .build();
+
+    let annotations = GithubReporter::new("/repo").to_annotations(&[messages]);
+
+    assert_eq!(
+      annotations,
+      vec![
+        "::error file=.npmrc,title=shined(npmrc%3Aconfig-file-not-found)::Config file not found: 100%25%0A%0ACreate .npmrc%0Awith registry"
+      ]
+    );
+  }
+
+  #[test]
+  fn should_use_notice_for_advice() {
+    let messages = Messages::builder()
+      .source_path("/repo/package.json".to_string())
+      .diagnostics(vec![Diagnostic::from(
+        MietteDiagnostic::new("hint").with_severity(Severity::Advice),
+      )])
+      .build();
+
+    let annotations = GithubReporter::new("/repo").to_annotations(&[messages]);
+
+    assert!(annotations[0].starts_with("::notice file=package.json,"));
+  }
+}
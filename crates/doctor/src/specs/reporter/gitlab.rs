@@ -0,0 +1,144 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+
+use doctor_core::{
+  JsonSeverity, Messages, fingerprint, fingerprint_occurrence, traits::DiagnosticCodeExt,
+};
+use serde_json::{Value, json};
+
+use super::Reporter;
+use crate::specs::Writer;
+
+/// Writes a GitLab Code Quality report
+///
+/// The fingerprint of every issue comes from [`doctor_core::fingerprint`], so the merge request
+/// widget can tell new and resolved issues apart between pipelines. Issues that would share one
+/// get an occurrence index like eslint-formatter-gitlab, see
+/// [`doctor_core::fingerprint_occurrence`].
+pub struct GitlabReporter {
+  cwd: PathBuf,
+}
+
+impl GitlabReporter {
+  pub fn new(cwd: impl AsRef<Path>) -> Self {
+    Self {
+      cwd: cwd.as_ref().to_path_buf(),
+    }
+  }
+
+  pub fn to_code_quality(&self, messages: &[Messages]) -> Value {
+    let mut issues = Vec::new();
+    // GitLab 会把相同 fingerprint 的问题合并成一个
+    let mut occurrences = HashMap::<String, usize>::new();
+
+    for messages in messages {
+      let path = messages.relative_path(&self.cwd);
+
+      let index = messages.line_index();
+
+      for diagnostic in messages.iter() {
+        let (begin, end) = diagnostic
+          .primary_location(&index)
+          .map_or((1, 1), |location| (location.start.row, location.end.row));
+
+        let severity = match JsonSeverity::from(diagnostic.severity) {
+          JsonSeverity::Error => "major",
+          JsonSeverity::Warning => "minor",
+          JsonSeverity::Advice => "info",
+        };
+
+        let fingerprint = fingerprint(&path, &messages.source_code, diagnostic);
+        let occurrence = occurrences.entry(fingerprint.clone()).or_default();
+        let fingerprint = fingerprint_occurrence(&fingerprint, *occurrence);
+        *occurrence += 1;
+
+        issues.push(json!({
+          "description": diagnostic.message,
+          "check_name": diagnostic.diagnostic_code_or_unknown().to_string(),
+          "fingerprint": fingerprint,
+          "severity": severity,
+          "location": {
+            "path": path,
+            "lines": { "begin": begin, "end": end },
+          },
+        }));
+      }
+    }
+
+    Value::Array(issues)
+  }
+}
+
+impl Reporter for GitlabReporter {
+  fn report(&self, messages: &[Messages], writer: &mut impl Writer) {
+    let json = serde_json::to_string_pretty(&self.to_code_quality(messages)).unwrap_or_default();
+    writer.write(json);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::{Diagnostic, testing::messages};
+  use miette::{LabeledSpan, MietteDiagnostic};
+
+  use super::*;
+
+  fn debugger(source: &str, offset: usize) -> Messages {
+    messages(
+      "lint",
+      "/repo/src/a.js",
+      source,
+      vec![Diagnostic::from(
+        MietteDiagnostic::new("`debugger` statement is not allowed")
+          .with_code("eslint(no-debugger)")
+          .with_label(LabeledSpan::at(offset..offset + 9, "")),
+      )],
+    )
+  }
+
+  #[test]
+  fn should_keep_fingerprint_when_lines_move() {
+    let reporter = GitlabReporter::new("/repo");
+
+    let before = reporter.to_code_quality(&[debugger("debugger;\n", 0)]);
+    let after = reporter.to_code_quality(&[debugger("let a = 1;\n\ndebugger;\n", 12)]);
+
+    assert_eq!(before[0]["check_name"], "eslint(no-debugger)");
+    assert_eq!(before[0]["severity"], "major");
+    assert_eq!(before[0]["location"]["path"], "src/a.js");
+    assert_eq!(before[0]["location"]["lines"]["begin"], 1);
+    assert_eq!(after[0]["location"]["lines"]["begin"], 3);
+    assert_eq!(before[0]["fingerprint"], after[0]["fingerprint"]);
+  }
+
+  #[test]
+  fn should_count_occurrences_of_identical_snippets() {
+    let reporter = GitlabReporter::new("/repo");
+    let mut twice = debugger(
+      "debugger;
+debugger;
+",
+      0,
+    );
+    let second = debugger(
+      "debugger;
+debugger;
+",
+      10,
+    )
+    .diagnostics;
+    twice.diagnostics.extend(second);
+
+    let issues = reporter.to_code_quality(&[twice]);
+    let single = reporter.to_code_quality(&[debugger(
+      "debugger;
+",
+      0,
+    )]);
+
+    assert_ne!(issues[0]["fingerprint"], issues[1]["fingerprint"]);
+    assert_eq!(issues[0]["fingerprint"], single[0]["fingerprint"]);
+  }
+}
@@ -0,0 +1,59 @@
+use std::path::{Path, PathBuf};
+
+use doctor_core::{JsonReport, Messages};
+
+use super::Reporter;
+use crate::specs::Writer;
+
+/// Writes a [`JsonReport`], file paths are relative to `cwd`
+pub struct JsonReporter {
+  cwd: PathBuf,
+}
+
+impl JsonReporter {
+  pub fn new(cwd: impl AsRef<Path>) -> Self {
+    Self {
+      cwd: cwd.as_ref().to_path_buf(),
+    }
+  }
+}
+
+impl Reporter for JsonReporter {
+  fn report(&self, messages: &[Messages], writer: &mut impl Writer) {
+    let report = JsonReport::new(messages, &self.cwd);
+    // 只包含 String 和数字，序列化不会失败
+    let json = serde_json::to_string_pretty(&report).unwrap_or_default();
+    writer.write(json);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::Diagnostic;
+
+  use super::*;
+  use crate::specs::StringWriter;
+
+  #[test]
+  fn should_write_versioned_json() {
+    let messages = Messages::builder()
+      .source_code("{}".to_string())
+      .source_path("/repo/package.json".to_string())
+      .diagnostics(vec![Diagnostic::from(
+        miette::MietteDiagnostic::new("Missing private field")
+          .with_code("shined(package-json:missing-private)"),
+      )])
+      .build();
+
+    let mut writer = StringWriter::default();
+    JsonReporter::new("/repo").report(&[messages], &mut writer);
+
+    let json: serde_json::Value = serde_json::from_str(&writer[0]).unwrap();
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["diagnostics"][0]["path"], "package.json");
+    assert_eq!(
+      json["diagnostics"][0]["code"],
+      "shined(package-json:missing-private)"
+    );
+  }
+}
@@ -0,0 +1,245 @@
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+use doctor_core::{Diagnostic, Messages, UNKNOWN, traits::DiagnosticCodeExt};
+use miette::{GraphicalReportHandler, GraphicalTheme, NamedSource, Severity};
+
+use super::{Reporter, xml::escape};
+use crate::specs::Writer;
+
+/// Writes a JUnit XML report
+///
+/// Every validator is a testsuite and every checked file a testcase of it. A file with error
+/// diagnostics fails with one failure carrying their rendered snippets, others are listed in
+/// `system-out`.
+pub struct JunitReporter {
+  cwd: PathBuf,
+}
+
+impl JunitReporter {
+  pub fn new(cwd: impl AsRef<Path>) -> Self {
+    Self {
+      cwd: cwd.as_ref().to_path_buf(),
+    }
+  }
+
+  fn is_error(diagnostic: &Diagnostic) -> bool {
+    matches!(diagnostic.severity, Some(Severity::Error) | None)
+  }
+
+  /// Render the miette snippet without colors, CI test viewers show it as plain text
+  fn render_snippet(messages: &Messages, diagnostic: &Diagnostic) -> String {
+    let handler = GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor());
+    let source = NamedSource::new(messages.source_path.clone(), messages.source_code.clone());
+    let report = miette::Report::new(diagnostic.inner.to_owned()).with_source_code(source);
+
+    let mut output = String::new();
+    match handler.render_report(&mut output, report.as_ref()) {
+      Ok(_) => output,
+      Err(_) => diagnostic.message.clone(),
+    }
+  }
+
+  /// The failure of a file with the error `diagnostics`, `None` when there are none
+  fn failure(messages: &Messages, diagnostics: &[&Diagnostic]) -> Option<String> {
+    let (message, code) = match diagnostics {
+      [] => return None,
+      [diagnostic] => (
+        diagnostic.message.clone(),
+        diagnostic.diagnostic_code_or_unknown().to_string(),
+      ),
+      _ => {
+        let mut codes = diagnostics
+          .iter()
+          .map(|diagnostic| diagnostic.diagnostic_code_or_unknown().to_string())
+          .collect::<Vec<_>>();
+        codes.sort();
+        codes.dedup();
+        (format!("{} problems", diagnostics.len()), codes.join(", "))
+      }
+    };
+
+    let snippets = diagnostics
+      .iter()
+      .map(|diagnostic| Self::render_snippet(messages, diagnostic))
+      .collect::<Vec<_>>();
+
+    Some(format!(
+      r#"      <failure message="{}" type="{}">{}</failure>"#,
+      escape(&message),
+      escape(&code),
+      escape(&snippets.join("\n")),
+    ))
+  }
+
+  /// The testcase of one file and whether it failed
+  fn testcase(&self, validator: &str, messages: &Messages) -> (String, bool) {
+    let name = messages.relative_path(&self.cwd);
+
+    // 一个文件只算一个失败的用例，多个错误合并到同一个 failure 里
+    let (errors, others): (Vec<&Diagnostic>, Vec<&Diagnostic>) = messages
+      .iter()
+      .partition(|diagnostic| Self::is_error(diagnostic));
+    let failure = Self::failure(messages, &errors);
+    let outputs = others
+      .iter()
+      .map(|diagnostic| escape(&Self::render_snippet(messages, diagnostic)))
+      .collect::<Vec<_>>();
+
+    let mut testcase = format!(
+      r#"    <testcase name="{}" classname="{}""#,
+      escape(&name),
+      escape(validator),
+    );
+
+    if failure.is_none() && outputs.is_empty() {
+      testcase.push_str(" />");
+      return (testcase, false);
+    }
+
+    testcase.push_str(">\n");
+    if let Some(failure) = &failure {
+      testcase.push_str(failure);
+      testcase.push('\n');
+    }
+    if !outputs.is_empty() {
+      testcase.push_str(&format!(
+        "      <system-out>{}</system-out>\n",
+        outputs.join("\n")
+      ));
+    }
+    testcase.push_str("    </testcase>");
+
+    (testcase, failure.is_some())
+  }
+
+  pub fn to_junit(&self, messages: &[Messages]) -> String {
+    let mut suites: BTreeMap<&str, Vec<&Messages>> = BTreeMap::new();
+    for messages in messages {
+      let validator = messages.validator.as_deref().unwrap_or(UNKNOWN);
+      suites.entry(validator).or_default().push(messages);
+    }
+
+    let mut total_tests = 0;
+    let mut total_failures = 0;
+    let mut body = Vec::new();
+
+    for (validator, messages) in suites {
+      let mut failures = 0;
+      let testcases = messages
+        .iter()
+        .map(|messages| {
+          let (testcase, failed) = self.testcase(validator, messages);
+          failures += usize::from(failed);
+          testcase
+        })
+        .collect::<Vec<_>>();
+
+      total_tests += testcases.len();
+      total_failures += failures;
+
+      body.push(format!(
+        r#"  <testsuite name="{}" tests="{}" failures="{}" errors="0" skipped="0">"#,
+        escape(validator),
+        testcases.len(),
+        failures,
+      ));
+      body.extend(testcases);
+      body.push("  </testsuite>".to_string());
+    }
+
+    let mut xml = vec![
+      r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+      format!(
+        r#"<testsuites name="doctor" tests="{}" failures="{}" errors="0">"#,
+        total_tests, total_failures,
+      ),
+    ];
+    xml.extend(body);
+    xml.push("</testsuites>".to_string());
+    xml.join("\n")
+  }
+}
+
+impl Reporter for JunitReporter {
+  fn report(&self, messages: &[Messages], writer: &mut impl Writer) {
+    writer.write(self.to_junit(messages));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::testing::messages;
+  use miette::{LabeledSpan, MietteDiagnostic};
+
+  use super::*;
+
+  #[test]
+  fn should_group_files_into_validator_suites() {
+    let clean = messages("lint", "/repo/src/a.js", "let a = 1;\n", vec![]);
+    let failing = messages(
+      "lint",
+      "/repo/src/b.js",
+      "if (a < b) debugger;\n",
+      vec![
+        Diagnostic::from(
+          MietteDiagnostic::new("`debugger` statement is not allowed")
+            .with_code("eslint(no-debugger)")
+            .with_label(LabeledSpan::at(11..19, "here")),
+        ),
+        Diagnostic::from(
+          MietteDiagnostic::new("Prefer const")
+            .with_code("eslint(prefer-const)")
+            .with_severity(Severity::Warning),
+        ),
+      ],
+    );
+    let two_errors = messages(
+      "lint",
+      "/repo/src/c.js",
+      "debugger;\ndebugger;\n",
+      vec![
+        Diagnostic::from(
+          MietteDiagnostic::new("`debugger` statement is not allowed")
+            .with_code("eslint(no-debugger)")
+            .with_label(LabeledSpan::at(0..8, "first")),
+        ),
+        Diagnostic::from(
+          MietteDiagnostic::new("`debugger` statement is not allowed")
+            .with_code("eslint(no-debugger)")
+            .with_label(LabeledSpan::at(10..18, "second")),
+        ),
+      ],
+    );
+    let npmrc = messages(
+      "npmrc",
+      "/repo/.npmrc",
+      "",
+      vec![Diagnostic::from(
+        MietteDiagnostic::new("Config file not found")
+          .with_code("shined(npmrc:config-file-not-found)"),
+      )],
+    );
+
+    let xml = JunitReporter::new("/repo").to_junit(&[clean, failing, two_errors, npmrc]);
+
+    assert!(xml.contains(r#"<testsuites name="doctor" tests="4" failures="3" errors="0">"#));
+    assert!(
+      xml.contains(r#"<testsuite name="lint" tests="3" failures="2" errors="0" skipped="0">"#)
+    );
+    assert_eq!(xml.matches("<failure ").count(), 3);
+    assert!(xml.contains(r#"<failure message="2 problems" type="eslint(no-debugger)">"#));
+    assert!(xml.contains("first") && xml.contains("second"));
+    assert!(xml.contains(r#"<testcase name="src/a.js" classname="lint" />"#));
+    assert!(xml.contains(
+      r#"<failure message="`debugger` statement is not allowed" type="eslint(no-debugger)">"#
+    ));
+    assert!(xml.contains("if (a &lt; b) debugger;"));
+    assert!(xml.contains("<system-out>"));
+    assert!(
+      xml.contains(r#"<testsuite name="npmrc" tests="1" failures="1" errors="0" skipped="0">"#)
+    );
+  }
+}
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use doctor_core::Messages;
+
+use super::Writer;
+
+mod checkstyle;
+mod github;
+mod gitlab;
+mod json;
+mod junit;
+mod sarif;
+mod xml;
+
+pub use checkstyle::CheckstyleReporter;
+pub use github::GithubReporter;
+pub use gitlab::GitlabReporter;
+pub use json::JsonReporter;
+pub use junit::JunitReporter;
+pub use sarif::SarifReporter;
+
+/// Machine readable output formats of [`super::Specifications::report_with_writer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+  Checkstyle,
+  Github,
+  Gitlab,
+  Json,
+  Junit,
+  Sarif,
+}
+
+pub trait Reporter {
+  fn report(&self, messages: &[Messages], writer: &mut impl Writer);
+}
+
+impl ReportFormat {
+  pub fn report(&self, cwd: &Path, messages: &[Messages], writer: &mut impl Writer) {
+    match self {
+      ReportFormat::Checkstyle => CheckstyleReporter::new(cwd).report(messages, writer),
+      ReportFormat::Github => GithubReporter::new(cwd).report(messages, writer),
+      ReportFormat::Gitlab => GitlabReporter::new(cwd).report(messages, writer),
+      ReportFormat::Json => JsonReporter::new(cwd).report(messages, writer),
+      ReportFormat::Junit => JunitReporter::new(cwd).report(messages, writer),
+      ReportFormat::Sarif => SarifReporter::new(cwd).report(messages, writer),
+    }
+  }
+}
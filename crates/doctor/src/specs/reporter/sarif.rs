@@ -0,0 +1,284 @@
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+};
+
+use doctor_core::{Diagnostic, JsonSeverity, Messages, loc::LineIndex, traits::DiagnosticCodeExt};
+use miette::LabeledSpan;
+use serde_json::{Value, json};
+
+use super::Reporter;
+use crate::specs::Writer;
+
+const SARIF_VERSION: &str = "2.1.0";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const TOOL_NAME: &str = "doctor";
+const TOOL_URI: &str = "https://github.com/sheinsight/doctor-engine";
+const SRCROOT: &str = "%SRCROOT%";
+
+/// Writes a SARIF 2.1.0 log
+///
+/// Every validator is a tool component (`tool.extensions`) and every code a rule of it,
+/// file paths are relative to `cwd`.
+pub struct SarifReporter {
+  cwd: PathBuf,
+}
+
+/// The rules of one validator, keyed by code with the first help and url seen
+#[derive(Default)]
+struct Component {
+  rules: BTreeMap<String, (Option<String>, Option<String>)>,
+}
+
+impl SarifReporter {
+  pub fn new(cwd: impl AsRef<Path>) -> Self {
+    Self {
+      cwd: cwd.as_ref().to_path_buf(),
+    }
+  }
+
+  fn root_uri(&self) -> String {
+    let root = self.cwd.to_string_lossy().replace('\\', "/");
+    let root = root.trim_end_matches('/');
+    if root.starts_with('/') {
+      format!("file://{}/", root)
+    } else {
+      // windows 路径，例如 C:/repo
+      format!("file:///{}/", root)
+    }
+  }
+
+  fn level(diagnostic: &Diagnostic) -> &'static str {
+    match JsonSeverity::from(diagnostic.severity) {
+      JsonSeverity::Error => "error",
+      JsonSeverity::Warning => "warning",
+      JsonSeverity::Advice => "note",
+    }
+  }
+
+  fn region(line_index: &LineIndex, label: &LabeledSpan) -> Value {
+    let location = line_index.location(label.offset(), label.len());
+    json!({
+      "startLine": location.start.row,
+      "startColumn": location.start.utf16_col,
+      "endLine": location.end.row,
+      "endColumn": location.end.utf16_col,
+    })
+  }
+
+  fn physical_location(uri: &str, region: Option<Value>) -> Value {
+    let mut physical_location = json!({
+      "artifactLocation": { "uri": uri, "uriBaseId": SRCROOT },
+    });
+    if let Some(region) = region {
+      physical_location["region"] = region;
+    }
+    json!({ "physicalLocation": physical_location })
+  }
+
+  fn collect_components(messages: &[Messages]) -> BTreeMap<String, Component> {
+    let mut components: BTreeMap<String, Component> = BTreeMap::new();
+    for messages in messages {
+      for diagnostic in messages.iter() {
+        let component = components
+          .entry(messages.validator_of(diagnostic))
+          .or_default();
+        component
+          .rules
+          .entry(diagnostic.diagnostic_code_or_unknown().to_string())
+          .or_insert_with(|| (diagnostic.help.clone(), diagnostic.url.clone()));
+      }
+    }
+    components
+  }
+
+  pub fn to_sarif(&self, messages: &[Messages]) -> Value {
+    let components = Self::collect_components(messages);
+
+    let extensions = components
+      .iter()
+      .map(|(name, component)| {
+        let rules = component
+          .rules
+          .iter()
+          .map(|(id, (help, url))| {
+            let mut rule = json!({ "id": id, "name": id });
+            if let Some(help) = help {
+              rule["help"] = json!({ "text": help });
+            }
+            if let Some(url) = url {
+              rule["helpUri"] = json!(url);
+            }
+            rule
+          })
+          .collect::<Vec<_>>();
+        json!({ "name": name, "rules": rules })
+      })
+      .collect::<Vec<_>>();
+
+    let mut results = Vec::new();
+
+    for messages in messages {
+      let uri = messages.relative_path(&self.cwd);
+      let line_index = messages.line_index();
+
+      for diagnostic in messages.iter() {
+        let validator = messages.validator_of(diagnostic);
+        let code = diagnostic.diagnostic_code_or_unknown().to_string();
+
+        // BTreeMap 的遍历顺序就是 extensions 和 rules 的下标
+        let component_index = components.keys().position(|k| *k == validator);
+        let rule_index = components
+          .get(&validator)
+          .and_then(|component| component.rules.keys().position(|k| *k == code));
+
+        let labels = diagnostic.labels.clone().unwrap_or_default();
+        let primary = labels.iter().position(|label| label.primary()).unwrap_or(0);
+
+        let location = Self::physical_location(
+          &uri,
+          labels
+            .get(primary)
+            .map(|label| Self::region(&line_index, label)),
+        );
+
+        let related_locations = labels
+          .iter()
+          .enumerate()
+          .filter(|(index, _)| *index != primary)
+          .map(|(index, label)| {
+            let mut related = Self::physical_location(&uri, Some(Self::region(&line_index, label)));
+            related["id"] = json!(index);
+            if let Some(text) = label.label() {
+              related["message"] = json!({ "text": text });
+            }
+            related
+          })
+          .collect::<Vec<_>>();
+
+        let mut result = json!({
+          "ruleId": code,
+          "level": Self::level(diagnostic),
+          "message": { "text": diagnostic.message },
+          "locations": [location],
+        });
+
+        if let (Some(component_index), Some(rule_index)) = (component_index, rule_index) {
+          result["rule"] = json!({
+            "id": code,
+            "index": rule_index,
+            "toolComponent": { "index": component_index },
+          });
+        }
+
+        if !related_locations.is_empty() {
+          result["relatedLocations"] = json!(related_locations);
+        }
+
+        results.push(result);
+      }
+    }
+
+    json!({
+      "version": SARIF_VERSION,
+      "$schema": SARIF_SCHEMA,
+      "runs": [{
+        "tool": {
+          "driver": {
+            "name": TOOL_NAME,
+            "version": env!("CARGO_PKG_VERSION"),
+            "informationUri": TOOL_URI,
+          },
+          "extensions": extensions,
+        },
+        "originalUriBaseIds": {
+          SRCROOT: { "uri": self.root_uri() },
+        },
+        "columnKind": "utf16CodeUnits",
+        "results": results,
+      }],
+    })
+  }
+}
+
+impl Reporter for SarifReporter {
+  fn report(&self, messages: &[Messages], writer: &mut impl Writer) {
+    let sarif = self.to_sarif(messages);
+    let json = serde_json::to_string_pretty(&sarif).unwrap_or_default();
+    writer.write(json);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::testing::messages;
+  use miette::MietteDiagnostic;
+
+  use super::*;
+
+  #[test]
+  fn should_map_validators_to_components_and_codes_to_rules() {
+    let npmrc = messages(
+      "npmrc",
+      "/repo/.npmrc",
+      "registry=https://a/\n",
+      vec![Diagnostic::from(
+        MietteDiagnostic::new("Invalid registry")
+          .with_code("shined(npmrc:invalid-registry)")
+          .with_severity(miette::Severity::Warning)
+          .with_label(LabeledSpan::new_primary_with_span(
+            Some("here".into()),
+            9..19,
+          )),
+      )],
+    );
+    let lint = messages(
+      "lint",
+      "/repo/src/a.js",
+      "debugger;\n",
+      vec![
+        Diagnostic::from(
+          MietteDiagnostic::new("`debugger` statement is not allowed")
+            .with_code("eslint(no-debugger)")
+            .with_label(LabeledSpan::at(0..9, "")),
+        ),
+        Diagnostic::from(
+          MietteDiagnostic::new("Unexpected empty statement")
+            .with_code("eslint(no-empty)")
+            .with_severity(miette::Severity::Advice),
+        ),
+      ],
+    );
+
+    let sarif = SarifReporter::new("/repo").to_sarif(&[npmrc, lint]);
+    let run = &sarif["runs"][0];
+
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(run["tool"]["extensions"][0]["name"], "lint");
+    assert_eq!(run["tool"]["extensions"][1]["name"], "npmrc");
+    assert_eq!(
+      run["tool"]["extensions"][0]["rules"][1]["id"],
+      "eslint(no-empty)"
+    );
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "shined(npmrc:invalid-registry)");
+    assert_eq!(result["level"], "warning");
+    assert_eq!(result["rule"]["toolComponent"]["index"], 1);
+    assert_eq!(result["rule"]["index"], 0);
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], ".npmrc");
+    assert_eq!(location["region"]["startLine"], 1);
+    assert_eq!(location["region"]["startColumn"], 10);
+    assert_eq!(location["region"]["endColumn"], 20);
+
+    let result = &run["results"][2];
+    assert_eq!(result["level"], "note");
+    assert_eq!(result["rule"]["index"], 1);
+    assert!(
+      result["locations"][0]["physicalLocation"]
+        .get("region")
+        .is_none()
+    );
+  }
+}
@@ -0,0 +1,33 @@
+/// Escape `text` for use in XML attributes and text nodes
+///
+/// Control characters are not allowed in XML 1.0 and are dropped, this also strips the
+/// escape character of ANSI color codes.
+pub(super) fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for ch in text.chars() {
+    match ch {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&apos;"),
+      '\t' | '\n' | '\r' => escaped.push(ch),
+      ch if ch.is_control() => {}
+      ch => escaped.push(ch),
+    }
+  }
+  escaped
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_escape_markup_and_drop_control_chars() {
+    assert_eq!(
+      escape("<a href=\"x\">'&'</a>\u{1b}[31m\n"),
+      "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;[31m\n"
+    );
+  }
+}
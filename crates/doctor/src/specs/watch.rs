@@ -0,0 +1,710 @@
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  path::{Path, PathBuf},
+  sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver, RecvTimeoutError},
+  },
+  thread::JoinHandle,
+  time::{Duration, Instant},
+};
+
+use doctor_core::{CachedDiagnostic, ChangedFiles, DOCTOR_VALIDATOR, Messages, ValidatorError};
+use doctor_walk::{WalkMatcher, WalkParallelJs};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use super::{Specifications, ValidatorOutcome};
+
+/// How often the watch thread checks whether it was stopped
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Config files next to `spec.json` and the validator that checks each of them
+const CONFIG_FILES: [(&str, &str); 3] = [
+  (".npmrc", doctor_npmrc::diagnostics::VALIDATOR),
+  (".node-version", doctor_node::diagnostics::VALIDATOR),
+  ("package.json", doctor_package_json::diagnostics::VALIDATOR),
+];
+
+pub struct WatchOpts {
+  /// How long to wait for more changes before validating again, editors often save in steps
+  pub debounce: Duration,
+}
+
+impl Default for WatchOpts {
+  fn default() -> Self {
+    Self {
+      debounce: Duration::from_millis(200),
+    }
+  }
+}
+
+/// The current messages of one validator for one file
+#[derive(Debug, Clone)]
+pub struct MessagesDelta {
+  pub validator: String,
+  pub source_path: String,
+  /// Replaces what was reported before for `validator` and `source_path`, empty when the
+  /// file is clean now or gone
+  pub messages: Vec<Messages>,
+}
+
+/// What changed in one round of [`Specifications::watch`]
+#[derive(Debug, Clone, Default)]
+pub struct WatchDelta {
+  /// The validators that ran in this round
+  pub validators: Vec<String>,
+  pub changes: Vec<MessagesDelta>,
+}
+
+impl WatchDelta {
+  fn extend(&mut self, other: WatchDelta) {
+    self.validators.extend(other.validators);
+    self.changes.extend(other.changes);
+  }
+}
+
+/// Stops watching when dropped
+pub struct WatchHandle {
+  stop: Arc<AtomicBool>,
+  thread: Option<JoinHandle<()>>,
+}
+
+impl WatchHandle {
+  /// Stop watching, waits for a running validation to finish
+  pub fn stop(mut self) {
+    self.shutdown();
+  }
+
+  fn shutdown(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+impl Drop for WatchHandle {
+  fn drop(&mut self) {
+    self.shutdown();
+  }
+}
+
+/// `(validator, source_path)`
+type Key = (String, String);
+
+/// Messages with diagnostics that were reported last, clean files are not kept
+#[derive(Default)]
+struct WatchState {
+  reported: BTreeMap<Key, Vec<Messages>>,
+}
+
+impl WatchState {
+  fn fingerprint(messages: &[Messages]) -> Vec<(&str, Vec<CachedDiagnostic>)> {
+    messages
+      .iter()
+      .map(|messages| {
+        let diagnostics = messages.iter().map(CachedDiagnostic::from).collect();
+        (messages.source_code.as_str(), diagnostics)
+      })
+      .collect()
+  }
+
+  /// Merge new outcomes, a reported entry `in_scope` that is not in them any more is cleared
+  fn apply(
+    &mut self,
+    outcomes: Vec<ValidatorOutcome>,
+    in_scope: impl Fn(&Key) -> bool,
+  ) -> WatchDelta {
+    let mut delta = WatchDelta::default();
+    let mut current: BTreeMap<Key, Vec<Messages>> = BTreeMap::new();
+
+    for outcome in outcomes {
+      for messages in outcome.messages {
+        if messages.is_empty() {
+          continue;
+        }
+        let key = (outcome.validator.clone(), messages.source_path.clone());
+        current.entry(key).or_default().push(messages);
+      }
+      delta.validators.push(outcome.validator);
+    }
+
+    let cleared = self
+      .reported
+      .keys()
+      .filter(|key| in_scope(key) && !current.contains_key(*key))
+      .cloned()
+      .collect::<Vec<_>>();
+    for key in cleared {
+      self.reported.remove(&key);
+      let (validator, source_path) = key;
+      delta.changes.push(MessagesDelta {
+        validator,
+        source_path,
+        messages: vec![],
+      });
+    }
+
+    for (key, messages) in current {
+      let unchanged = self
+        .reported
+        .get(&key)
+        .is_some_and(|reported| Self::fingerprint(reported) == Self::fingerprint(&messages));
+      if unchanged {
+        continue;
+      }
+      self.reported.insert(key.clone(), messages.clone());
+      let (validator, source_path) = key;
+      delta.changes.push(MessagesDelta {
+        validator,
+        source_path,
+        messages,
+      });
+    }
+
+    delta
+  }
+}
+
+/// The directories being watched, each one on its own so that ignored trees such as
+/// `node_modules` are never watched
+struct WatchedDirs {
+  watcher: RecommendedWatcher,
+  root: PathBuf,
+  dirs: BTreeSet<PathBuf>,
+}
+
+impl WatchedDirs {
+  fn new(watcher: RecommendedWatcher, root: PathBuf) -> Self {
+    Self {
+      watcher,
+      root,
+      dirs: BTreeSet::new(),
+    }
+  }
+
+  /// `.sfconfig` is hidden, the walk never enters it
+  fn sfconfig(&self) -> PathBuf {
+    self.root.join(".sfconfig")
+  }
+
+  fn watch(&mut self, dir: PathBuf) -> notify::Result<()> {
+    if !self.dirs.contains(&dir) {
+      self.watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+      self.dirs.insert(dir);
+    }
+    Ok(())
+  }
+
+  /// Watch exactly the directories the walk enters, and `.sfconfig`
+  ///
+  /// Without a `matcher`, when the `ignore` of spec.json is not valid, only `cwd` is watched
+  /// besides `.sfconfig`, fixing spec.json syncs again.
+  fn sync(&mut self, matcher: Option<&WalkMatcher>) -> notify::Result<()> {
+    // ignore 写错时由 sfconfig 校验器报告，lint 和 syntax 也跑不了
+    let mut dirs = matcher
+      .map_or_else(
+        || vec![self.root.clone()],
+        |matcher| matcher.dirs(&self.root),
+      )
+      .into_iter()
+      .collect::<BTreeSet<_>>();
+    let sfconfig = self.sfconfig();
+    if sfconfig.is_dir() {
+      dirs.insert(sfconfig);
+    }
+
+    let stale = self.dirs.difference(&dirs).cloned().collect::<Vec<_>>();
+    for dir in stale {
+      let _ = self.watcher.unwatch(&dir);
+      self.dirs.remove(&dir);
+    }
+    for dir in dirs {
+      self.watch(dir)?;
+    }
+    Ok(())
+  }
+
+  /// Follow directories that were created or removed in a batch of events
+  fn update(&mut self, matcher: Option<&WalkMatcher>, paths: &BTreeSet<PathBuf>) {
+    for path in paths {
+      if path.is_dir() {
+        let dirs = if *path == self.sfconfig() {
+          vec![path.clone()]
+        } else {
+          matcher
+            .map(|matcher| matcher.dirs(path))
+            .unwrap_or_default()
+        };
+        for dir in dirs {
+          // 目录可能刚建好又被删掉，下一批事件会再同步
+          let _ = self.watch(dir);
+        }
+      } else if self.dirs.remove(path) {
+        let _ = self.watcher.unwatch(path);
+      }
+    }
+  }
+}
+
+/// What a batch of file events asks to validate again
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Changes {
+  /// `spec.json` or `pnpm-workspace.yaml` changed, every validator runs again
+  all: bool,
+  /// Config file validators whose file changed
+  validators: BTreeSet<&'static str>,
+  /// Changed sources for lint and syntax, deleted ones included
+  sources: BTreeSet<PathBuf>,
+}
+
+impl Changes {
+  /// `packages` are the directories of the workspace packages
+  fn classify(
+    root: &Path,
+    packages: &[PathBuf],
+    matcher: Option<&WalkMatcher>,
+    paths: &BTreeSet<PathBuf>,
+  ) -> Self {
+    let mut changes = Self::default();
+    let spec = root.join(".sfconfig").join("spec.json");
+    // 包的增删会改变要检查的 package.json
+    let pnpm_workspace = root.join("pnpm-workspace.yaml");
+
+    for path in paths {
+      if *path == spec || *path == pnpm_workspace {
+        changes.all = true;
+      } else if packages.iter().any(|dir| dir.join("package.json") == *path) {
+        changes
+          .validators
+          .insert(doctor_package_json::diagnostics::VALIDATOR);
+      } else if let Some((_, validator)) = CONFIG_FILES
+        .iter()
+        .find(|(file, _)| root.join(file) == *path)
+      {
+        changes.validators.insert(validator);
+      } else if matcher.is_some_and(|matcher| matcher.accepts(path)) {
+        changes.sources.insert(path.clone());
+      }
+    }
+
+    changes
+  }
+
+  fn is_empty(&self) -> bool {
+    !self.all && self.validators.is_empty() && self.sources.is_empty()
+  }
+}
+
+/// Wait for file events and collect them until `debounce` passes, `None` once stopped
+fn next_batch(
+  rx: &Receiver<notify::Result<Event>>,
+  stop: &AtomicBool,
+  debounce: Duration,
+) -> Option<BTreeSet<PathBuf>> {
+  let mut paths = BTreeSet::new();
+  let collect = |paths: &mut BTreeSet<PathBuf>, event: notify::Result<Event>| {
+    // 读文件也会产生 Access 事件，只关心内容和文件的增删改
+    match event {
+      Ok(event) if !matches!(event.kind, EventKind::Access(_)) => paths.extend(event.paths),
+      _ => {}
+    }
+  };
+
+  loop {
+    if stop.load(Ordering::Relaxed) {
+      return None;
+    }
+    match rx.recv_timeout(POLL_INTERVAL) {
+      Ok(event) => {
+        collect(&mut paths, event);
+        break;
+      }
+      Err(RecvTimeoutError::Timeout) => continue,
+      Err(RecvTimeoutError::Disconnected) => return None,
+    }
+  }
+
+  let deadline = Instant::now() + debounce;
+  while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+    match rx.recv_timeout(timeout) {
+      Ok(event) => collect(&mut paths, event),
+      Err(RecvTimeoutError::Timeout) => break,
+      Err(RecvTimeoutError::Disconnected) => return None,
+    }
+  }
+
+  Some(paths)
+}
+
+impl Specifications {
+  /// Validate everything once, then again whenever files under `cwd` change
+  ///
+  /// Only the affected validators run again: lint and syntax for changed sources, a config
+  /// file validator for its file, and all of them when `.sfconfig/spec.json` changes. Sources
+  /// follow the `ignore` of spec.json and the gitignore files like [`WalkParallelJs`], only
+  /// the directories it enters are watched.
+  ///
+  /// `on_delta` is called on the watch thread, first with every file that has diagnostics,
+  /// then only with what changed.
+  pub fn watch(
+    &self,
+    opts: WatchOpts,
+    mut on_delta: impl FnMut(WatchDelta) + Send + 'static,
+  ) -> Result<WatchHandle, ValidatorError> {
+    // notify 报告的是绝对路径，cwd 也要统一成绝对路径才能比较
+    let root = self.cwd.canonicalize()?;
+    let specifications = Specifications {
+      cwd: root.clone(),
+      ..self.clone()
+    };
+
+    // 每批事件只建一次，ignore 写错时为 None
+    let matcher = {
+      let root = root.clone();
+      move |specifications: &Specifications| {
+        WalkParallelJs::builder()
+          .cwd(root.clone())
+          .ignore(specifications.sfconfig().unwrap_or_default().ignore)
+          .build()
+          .matcher()
+          .ok()
+      }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let watcher =
+      notify::recommended_watcher(tx).map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
+    let mut watched = WatchedDirs::new(watcher, root.clone());
+    watched
+      .sync(matcher(&specifications).as_ref())
+      .map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = std::thread::spawn({
+      let stop = stop.clone();
+      move || {
+        // 线程退出前一直持有 watcher
+        let mut watched = watched;
+        let mut state = WatchState::default();
+
+        let everything = Changes {
+          all: true,
+          ..Changes::default()
+        };
+        on_delta(specifications.rerun(&mut state, &everything));
+
+        while let Some(paths) = next_batch(&rx, &stop, opts.debounce) {
+          let matcher = matcher(&specifications);
+
+          let packages = specifications
+            .workspace()
+            .0
+            .packages
+            .into_iter()
+            .map(|package| package.dir)
+            .collect::<Vec<_>>();
+
+          let changes = Changes::classify(&root, &packages, matcher.as_ref(), &paths);
+          // ignore 可能变了，要看的目录跟着重新算
+          if changes.all {
+            let _ = watched.sync(matcher.as_ref());
+          } else {
+            watched.update(matcher.as_ref(), &paths);
+          }
+          if changes.is_empty() {
+            continue;
+          }
+
+          let delta = specifications.rerun(&mut state, &changes);
+          if !delta.changes.is_empty() {
+            on_delta(delta);
+          }
+        }
+      }
+    });
+
+    Ok(WatchHandle {
+      stop,
+      thread: Some(thread),
+    })
+  }
+
+  fn rerun(&self, state: &mut WatchState, changes: &Changes) -> WatchDelta {
+    // 连 registry 都建不起来时（比如 spec.json 读不了），报在 doctor 名下
+    let crashed = |error| vec![ValidatorOutcome::crashed(DOCTOR_VALIDATOR, error)];
+
+    if changes.all {
+      let outcomes = self.run_all().unwrap_or_else(crashed);
+      // spec.json 写坏时只有 sfconfig 校验器会跑，其余校验器上次的结果先留着
+      let ran = outcomes
+        .iter()
+        .map(|outcome| outcome.validator.clone())
+        .collect::<BTreeSet<_>>();
+      // doctor 名下的崩溃每次全量运行都会重新报告
+      return state.apply(outcomes, |(validator, _)| {
+        ran.contains(validator) || validator == DOCTOR_VALIDATOR
+      });
+    }
+
+    let mut delta = WatchDelta::default();
+
+    if !changes.validators.is_empty() {
+      // 工作区发现失败的报告只在全量运行时更新
+      let (workspace, _) = self.workspace();
+      let outcomes = match self.registry_of(&workspace) {
+        Ok(registry) => self.run_validators(
+          registry
+            .entries()
+            .filter(|(meta, _)| changes.validators.contains(meta.id.as_str()))
+            .collect(),
+          false,
+          &workspace,
+        ),
+        Err(error) => crashed(error),
+      };
+      delta.extend(state.apply(outcomes, |(validator, _)| {
+        changes.validators.contains(validator.as_str())
+      }));
+    }
+
+    if !changes.sources.is_empty() {
+      // 只给出源文件时，配置文件的检查不会被注册
+      let specifications = self
+        .clone()
+        .with_files(ChangedFiles::new(&self.cwd, &changes.sources));
+      let outcomes = specifications.run_all().unwrap_or_else(crashed);
+      let validators = outcomes
+        .iter()
+        .map(|outcome| outcome.validator.clone())
+        .collect::<BTreeSet<_>>();
+      delta.extend(state.apply(outcomes, |(validator, source_path)| {
+        validators.contains(validator) && changes.sources.contains(Path::new(source_path))
+      }));
+    }
+
+    delta
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::{Diagnostic, Ignore};
+  use miette::MietteDiagnostic;
+
+  use super::*;
+
+  fn outcome(validator: &str, files: &[(&str, &str)]) -> ValidatorOutcome {
+    let messages = files
+      .iter()
+      .map(|(path, message)| {
+        let diagnostics = if message.is_empty() {
+          vec![]
+        } else {
+          vec![Diagnostic::from(MietteDiagnostic::new(*message))]
+        };
+        Messages::builder()
+          .source_path(path.to_string())
+          .diagnostics(diagnostics)
+          .validator(validator)
+          .build()
+      })
+      .collect();
+
+    ValidatorOutcome {
+      validator: validator.to_string(),
+      messages,
+      error: None,
+      duration: Duration::ZERO,
+    }
+  }
+
+  fn summary(delta: &WatchDelta) -> Vec<(&str, &str, usize)> {
+    delta
+      .changes
+      .iter()
+      .map(|change| {
+        (
+          change.validator.as_str(),
+          change.source_path.as_str(),
+          change.messages.len(),
+        )
+      })
+      .collect()
+  }
+
+  #[test]
+  fn should_only_report_changed_files() {
+    let mut state = WatchState::default();
+
+    let first = state.apply(
+      vec![outcome(
+        "lint",
+        &[("/a.js", "no-debugger"), ("/b.js", "no-var"), ("/c.js", "")],
+      )],
+      |_| true,
+    );
+    assert_eq!(
+      summary(&first),
+      vec![("lint", "/a.js", 1), ("lint", "/b.js", 1)]
+    );
+
+    // a.js 没变，b.js 修好了，c.js 新增了问题
+    let sources = ["/a.js", "/b.js", "/c.js"].map(PathBuf::from);
+    let second = state.apply(
+      vec![outcome(
+        "lint",
+        &[("/a.js", "no-debugger"), ("/b.js", ""), ("/c.js", "eqeqeq")],
+      )],
+      |(_, path)| sources.contains(&PathBuf::from(path)),
+    );
+    assert_eq!(second.validators, vec!["lint"]);
+    assert_eq!(
+      summary(&second),
+      vec![("lint", "/b.js", 0), ("lint", "/c.js", 1)]
+    );
+  }
+
+  #[test]
+  fn should_keep_reported_files_out_of_scope() {
+    let mut state = WatchState::default();
+    state.apply(
+      vec![
+        outcome("lint", &[("/a.js", "no-debugger")]),
+        outcome("npmrc", &[("/.npmrc", "missing registry")]),
+      ],
+      |_| true,
+    );
+
+    let delta = state.apply(vec![outcome("npmrc", &[])], |(validator, _)| {
+      validator == "npmrc"
+    });
+
+    assert_eq!(summary(&delta), vec![("npmrc", "/.npmrc", 0)]);
+    assert!(
+      state
+        .reported
+        .contains_key(&("lint".to_string(), "/a.js".to_string()))
+    );
+  }
+
+  #[test]
+  fn should_only_watch_dirs_the_walk_enters() {
+    let root = std::env::temp_dir().join(format!("doctor-watch-dirs-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    for dir in ["src", "node_modules/a", ".sfconfig"] {
+      std::fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    let root = root.canonicalize().unwrap();
+
+    let (tx, _rx) = mpsc::channel();
+    let watcher = notify::recommended_watcher(tx).unwrap();
+    let mut watched = WatchedDirs::new(watcher, root.clone());
+    let matcher = WalkParallelJs::builder()
+      .cwd(root.clone())
+      .build()
+      .matcher()
+      .unwrap();
+    watched.sync(Some(&matcher)).unwrap();
+    assert_eq!(
+      watched.dirs,
+      BTreeSet::from([root.clone(), root.join(".sfconfig"), root.join("src")])
+    );
+
+    std::fs::create_dir_all(root.join("src/lib/util")).unwrap();
+    std::fs::create_dir_all(root.join("node_modules/b")).unwrap();
+    std::fs::remove_dir(root.join(".sfconfig")).unwrap();
+    let paths = ["src/lib", "node_modules/b", ".sfconfig"]
+      .iter()
+      .map(|path| root.join(path))
+      .collect();
+    watched.update(Some(&matcher), &paths);
+    assert_eq!(
+      watched.dirs,
+      BTreeSet::from([
+        root.clone(),
+        root.join("src"),
+        root.join("src/lib"),
+        root.join("src/lib/util")
+      ])
+    );
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn should_watch_root_when_ignore_is_invalid() {
+    let root = std::env::temp_dir().join(format!("doctor-watch-ignore-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    for dir in ["src", ".sfconfig"] {
+      std::fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    let root = root.canonicalize().unwrap();
+
+    let (tx, _rx) = mpsc::channel();
+    let watcher = notify::recommended_watcher(tx).unwrap();
+    let mut watched = WatchedDirs::new(watcher, root.clone());
+    let matcher = WalkParallelJs::builder()
+      .cwd(root.clone())
+      .ignore(Ignore(vec!["src/{a".to_string()]))
+      .build()
+      .matcher()
+      .ok();
+    assert!(matcher.is_none());
+    watched.sync(None).unwrap();
+    assert_eq!(
+      watched.dirs,
+      BTreeSet::from([root.clone(), root.join(".sfconfig")])
+    );
+
+    let paths = BTreeSet::from([root.join("src/a.ts")]);
+    assert!(Changes::classify(&root, &[], None, &paths).is_empty());
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn should_classify_changed_paths() {
+    let root = PathBuf::from("/repo");
+    let matcher = WalkParallelJs::builder()
+      .cwd(root.clone())
+      .build()
+      .matcher()
+      .unwrap();
+    let matcher = Some(&matcher);
+    let paths = [
+      "src/a.ts",
+      ".npmrc",
+      "package.json",
+      "node_modules/x/index.js",
+      "README.md",
+      "packages/a/package.json",
+    ]
+    .iter()
+    .map(|path| root.join(path))
+    .collect();
+
+    let packages = [root.join("packages/a")];
+    let changes = Changes::classify(&root, &packages, matcher, &paths);
+
+    assert!(!changes.all);
+    assert_eq!(
+      changes.validators,
+      BTreeSet::from(["npmrc", "package-json"])
+    );
+
+    let package_json = BTreeSet::from([root.join("packages/a/package.json")]);
+    assert_eq!(
+      Changes::classify(&root, &packages, matcher, &package_json).validators,
+      BTreeSet::from(["package-json"])
+    );
+    assert_eq!(changes.sources, BTreeSet::from([root.join("src/a.ts")]));
+
+    let spec = BTreeSet::from([root.join(".sfconfig/spec.json")]);
+    assert!(Changes::classify(&root, &[], matcher, &spec).all);
+
+    let pnpm_workspace = BTreeSet::from([root.join("pnpm-workspace.yaml")]);
+    assert!(Changes::classify(&root, &[], matcher, &pnpm_workspace).all);
+  }
+}
@@ -0,0 +1,41 @@
+use std::{env, fs, path::PathBuf};
+
+/// Pass the exact oxc_linter build to the result cache salt as `DOCTOR_OXC_LINTER`
+///
+/// oxc comes from git, so the source with its commit is taken from the workspace lock file,
+/// the version alone does not change between commits.
+fn main() {
+  let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+  let lock = manifest_dir.join("..").join("..").join("Cargo.lock");
+  println!("cargo:rerun-if-changed={}", lock.display());
+
+  let engine = fs::read_to_string(&lock)
+    .ok()
+    .and_then(|lock| locked_package(&lock, "oxc_linter"))
+    .unwrap_or_else(|| "unknown".to_string());
+  println!("cargo:rustc-env=DOCTOR_OXC_LINTER={engine}");
+}
+
+/// `version+source` of the locked package `name`
+fn locked_package(lock: &str, name: &str) -> Option<String> {
+  let name = format!("name = \"{name}\"");
+  let package = lock
+    .split("[[package]]")
+    .find(|package| package.lines().any(|line| line.trim() == name))?;
+  let field = |key: &str| {
+    package.lines().find_map(|line| {
+      let value = line
+        .trim()
+        .strip_prefix(key)?
+        .trim_start()
+        .strip_prefix('=')?;
+      Some(value.trim().trim_matches('"').to_string())
+    })
+  };
+
+  Some(format!(
+    "{}+{}",
+    field("version")?,
+    field("source").unwrap_or_default()
+  ))
+}
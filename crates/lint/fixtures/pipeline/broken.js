@@ -0,0 +1,2 @@
+const b = { x: 1, x: 2 };
+let = ;
@@ -0,0 +1,3 @@
+const a = { x: 1, x: 2 };
+
+export default a;
@@ -0,0 +1,4 @@
+const c = { x: 1, x: 2 };
+const n = 1__0;
+
+export default { c, n };
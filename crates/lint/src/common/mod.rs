@@ -3,5 +3,6 @@ pub mod error;
 mod file_diagnostic;
 pub mod lint_mode;
 pub mod named_source;
+pub mod severity;
 
 pub use file_diagnostic::FileDiagnostic;
@@ -0,0 +1,197 @@
+use std::collections::BTreeSet;
+
+use doctor_core::SeverityOverrides;
+use serde_json::{Value, json};
+
+/// oxlint 的插件名和它报出来的诊断 code 前缀不一样
+const PLUGIN_SCOPES: [(&str, &str); 12] = [
+  ("eslint", "eslint"),
+  ("typescript", "typescript-eslint"),
+  ("unicorn", "eslint-plugin-unicorn"),
+  ("react", "eslint-plugin-react"),
+  ("react_perf", "eslint-plugin-react-perf"),
+  ("jsx_a11y", "eslint-plugin-jsx-a11y"),
+  ("import", "eslint-plugin-import"),
+  ("jest", "eslint-plugin-jest"),
+  ("vitest", "eslint-plugin-vitest"),
+  ("promise", "eslint-plugin-promise"),
+  ("nextjs", "eslint-plugin-next"),
+  ("oxc", "oxc"),
+];
+
+/// The diagnostic code reported for an oxlintrc rule key
+///
+/// # Examples
+///
+/// ```rust
+/// use doctor_lint::rule_code;
+///
+/// assert_eq!(rule_code("eslint/no-debugger"), "eslint(no-debugger)");
+/// assert_eq!(rule_code("typescript/no-misused-new"), "typescript-eslint(no-misused-new)");
+/// ```
+pub fn rule_code(key: &str) -> String {
+  let (plugin, rule) = key.split_once('/').unwrap_or(("eslint", key));
+  let scope = PLUGIN_SCOPES
+    .iter()
+    .find(|(name, _)| *name == plugin)
+    .map_or(plugin, |(_, scope)| scope);
+  format!("{}({})", scope, rule)
+}
+
+fn is_off(config: &Value) -> bool {
+  let level = match config {
+    Value::Array(items) => items.first().unwrap_or(&Value::Null),
+    level => level,
+  };
+  match level {
+    Value::Number(n) => n.as_u64() == Some(0),
+    Value::String(s) => matches!(s.as_str(), "off" | "allow"),
+    _ => false,
+  }
+}
+
+/// Codes of the rules an oxlintrc json enables, including the ones of `overrides`
+pub(crate) fn enabled_rule_codes(config: &Value) -> Vec<String> {
+  let overrides = config
+    .get("overrides")
+    .and_then(Value::as_array)
+    .into_iter()
+    .flatten()
+    .map(|item| item.get("rules"));
+
+  let mut codes = BTreeSet::new();
+  for rules in std::iter::once(config.get("rules"))
+    .chain(overrides)
+    .flatten()
+    .filter_map(Value::as_object)
+  {
+    for (key, config) in rules {
+      if !is_off(config) {
+        codes.insert(rule_code(key));
+      }
+    }
+  }
+
+  codes.into_iter().collect()
+}
+
+fn apply_to_rules(rules: &mut Value, overrides: &SeverityOverrides) {
+  let Some(rules) = rules.as_object_mut() else {
+    return;
+  };
+
+  for (key, config) in rules.iter_mut() {
+    let code = rule_code(key);
+    let Some(level) = overrides.level_of(&code) else {
+      continue;
+    };
+    // 通配符只调整开着的规则，关掉的规则只有写明 code 才能打开
+    if is_off(config) && !overrides.contains_key(&code) {
+      continue;
+    }
+    let level = json!(level.to_rule_level());
+    match config {
+      // 保留规则自己的选项，只替换等级
+      Value::Array(items) if !items.is_empty() => items[0] = level,
+      _ => *config = json!([level]),
+    }
+  }
+}
+
+/// Apply `overrides` to the rules of an oxlintrc json, including the ones of `overrides`
+///
+/// A glob such as `eslint(*)` only re-levels the rules that are on, turning a rule that is off
+/// back on takes its exact code.
+pub(crate) fn apply_rule_severity(config: &mut Value, overrides: &SeverityOverrides) {
+  if overrides.is_empty() {
+    return;
+  }
+
+  if let Some(rules) = config.get_mut("rules") {
+    apply_to_rules(rules, overrides);
+  }
+
+  if let Some(Value::Array(items)) = config.get_mut("overrides") {
+    for item in items {
+      if let Some(rules) = item.get_mut("rules") {
+        apply_to_rules(rules, overrides);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::SeverityLevel;
+
+  use super::*;
+
+  #[test]
+  fn should_collect_enabled_rule_codes() {
+    let config = json!({
+      "rules": { "eslint/no-debugger": [2], "eslint/no-fallthrough": [0, {}] },
+      "overrides": [{ "rules": { "typescript/no-misused-new": "deny" } }]
+    });
+
+    assert_eq!(
+      enabled_rule_codes(&config),
+      vec!["eslint(no-debugger)", "typescript-eslint(no-misused-new)"]
+    );
+  }
+
+  #[test]
+  fn should_relevel_rules_and_keep_options() {
+    let mut config = json!({
+      "rules": {
+        "eslint/no-empty": [2, { "allowEmptyCatch": true }],
+        "eslint/no-debugger": [2],
+      },
+      "overrides": [{
+        "files": ["*.tsx"],
+        "rules": { "react/jsx-no-undef": [2] }
+      }]
+    });
+
+    let mut overrides = SeverityOverrides::default();
+    overrides.insert("eslint(no-empty)".to_string(), SeverityLevel::Warn);
+    overrides.insert("eslint-plugin-react(*)".to_string(), SeverityLevel::Off);
+
+    apply_rule_severity(&mut config, &overrides);
+
+    assert_eq!(
+      config["rules"]["eslint/no-empty"],
+      json!([1, { "allowEmptyCatch": true }])
+    );
+    assert_eq!(config["rules"]["eslint/no-debugger"], json!([2]));
+    assert_eq!(
+      config["overrides"][0]["rules"]["react/jsx-no-undef"],
+      json!([0])
+    );
+  }
+
+  #[test]
+  fn should_keep_off_rules_off_for_globs() {
+    let mut config = json!({
+      "rules": {
+        "eslint/no-fallthrough": [0, { "commentPattern": "break[\\s\\w]*omitted" }],
+        "eslint/no-debugger": [2],
+        "react/react-in-jsx-scope": 0,
+      }
+    });
+
+    let mut overrides = SeverityOverrides::default();
+    overrides.insert("eslint(*)".to_string(), SeverityLevel::Warn);
+    overrides.insert("eslint-plugin-react(*)".to_string(), SeverityLevel::Error);
+
+    apply_rule_severity(&mut config, &overrides);
+
+    assert_eq!(config["rules"]["eslint/no-fallthrough"][0], json!(0));
+    assert_eq!(config["rules"]["eslint/no-debugger"], json!([1]));
+    assert_eq!(config["rules"]["react/react-in-jsx-scope"], json!(0));
+
+    overrides.insert("eslint(no-fallthrough)".to_string(), SeverityLevel::Error);
+    apply_rule_severity(&mut config, &overrides);
+
+    assert_eq!(config["rules"]["eslint/no-fallthrough"][0], json!(2));
+  }
+}
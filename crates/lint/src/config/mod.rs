@@ -1,9 +1,16 @@
 mod oxlintrc_builder;
 mod react_config;
 mod sfconfig;
+mod sfconfig_diagnostics;
+mod sfconfig_extends;
+mod sfconfig_validator;
 mod typescript_config;
 
 pub use oxlintrc_builder::{GlobalValue, Globals, OxlintrcBuilder};
 pub use react_config::{ReactConfig, ReactRuntime};
 pub use sfconfig::Sfconfig;
+pub use sfconfig_diagnostics::{SFCONFIG_VALIDATOR, SfconfigDiagnosticFactory};
+pub(crate) use sfconfig_extends::ExtendsResolver;
+pub use sfconfig_extends::{PRESET_PREFIX, SFCONFIG_PRESETS};
+pub use sfconfig_validator::{SfconfigValidator, check_sfconfig};
 pub use typescript_config::TypescriptConfig;
@@ -2,6 +2,7 @@ use std::ops::{Deref, DerefMut};
 
 use oxc_linter::Oxlintrc;
 use rustc_hash::FxHashMap;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumString;
 
@@ -30,7 +31,7 @@ use crate::{category::Category, inner::Category20250601Inner};
  * 👍 8. 要知道 category，主要是用来区分版本信息的
  */
 
-#[derive(Debug, Clone, Serialize, Deserialize, EnumString)]
+#[derive(Debug, Clone, Serialize, Deserialize, EnumString, JsonSchema)]
 #[strum(serialize_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum GlobalValue {
@@ -38,7 +39,7 @@ pub enum GlobalValue {
   Readonly,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Globals(pub FxHashMap<String, GlobalValue>);
 
 impl Default for Globals {
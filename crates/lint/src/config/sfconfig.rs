@@ -1,26 +1,133 @@
-use std::{fs::read_to_string, path::Path};
+use std::{
+  fs::read_to_string,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+};
 
-use doctor_core::{Ignore, ValidatorError};
+use doctor_core::{Ignore, Policy, SeverityOverrides, ValidatorError};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::Globals;
+use super::{ExtendsResolver, Globals};
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+/// `.sfconfig/spec.json`, see [`Sfconfig::json_schema`] for editor autocompletion
+#[derive(Debug, Clone, Deserialize, Serialize, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub struct Sfconfig {
+  /// The schema editors complete this file with, not read by doctor
+  #[serde(default, rename = "$schema", skip_serializing_if = "Option::is_none")]
+  pub schema: Option<String>,
+  /// Configs merged in before this one, in order: built-in presets like `doctor:recommended`
+  /// or paths relative to this file. Objects merge key by key, `ignore` lists are appended
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub extends: Vec<String>,
   #[serde(default)]
   pub globals: Globals,
   #[serde(default)]
   pub ignore: Ignore,
+  /// Severity overrides keyed by code or glob, e.g. `{ "eslint(no-debugger)": "warn" }`
+  #[serde(default)]
+  pub severity: SeverityOverrides,
+  /// Directory of the result cache relative to the project root, defaults to `.sfconfig/.cache`
+  #[serde(default, rename = "cacheDir")]
+  pub cache_dir: Option<PathBuf>,
+  /// Node versions, registries and package.json rules, unset fields keep the built-in policy
+  #[serde(default)]
+  pub policy: Policy,
 }
 
 impl Sfconfig {
+  /// The config with its `extends` chain resolved, a missing file is the default config and
+  /// any other problem is an error
+  ///
+  /// Unknown keys are ignored here, [`super::SfconfigValidator`] reports them together with
+  /// everything else wrong in the file.
   pub fn parse<P: AsRef<Path>>(path: P) -> Result<Self, ValidatorError> {
-    if let Ok(config) = read_to_string(path) {
-      let config = serde_json::from_str::<Sfconfig>(&config)
-        .map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
-      return Ok(config);
-    }
+    let path = path.as_ref();
+    let config = match read_to_string(path) {
+      Ok(config) => config,
+      Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+      Err(e) => return Err(e.into()),
+    };
+
+    let invalid = |e: serde_json::Error| {
+      ValidatorError::Unknown(format!("Invalid config {}: {}", path.display(), e).into())
+    };
+
+    let config = serde_json::from_str(&config).map_err(invalid)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let resolved = ExtendsResolver::new(path).resolve(config, dir)?;
+
+    serde_json::from_value::<Sfconfig>(resolved).map_err(invalid)
+  }
+
+  /// The JSON Schema of spec.json, reference it with `$schema` for editor autocompletion
+  pub fn json_schema() -> serde_json::Value {
+    schemars::schema_for!(Sfconfig).to_value()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use doctor_core::{IGNORE_PATTERNS, SeverityLevel, testing::project};
+
+  use super::*;
+
+  #[test]
+  fn should_merge_extends_in_order() {
+    let dir = project(
+      "sfconfig-extends",
+      &[
+        (
+          "shared/base.json",
+          r#"{ "globals": { "a": "readonly", "b": "readonly" }, "ignore": ["dist/**"], "severity": { "eslint(no-debugger)": "off" } }"#,
+        ),
+        (
+          ".sfconfig/spec.json",
+          r#"{ "extends": ["doctor:relaxed", "../shared/base.json"], "globals": { "b": "writable" }, "ignore": ["lib/**"] }"#,
+        ),
+      ],
+    );
+
+    let config = Sfconfig::parse(dir.join(".sfconfig/spec.json")).unwrap();
+
+    assert!(config.extends.is_empty());
+    assert_eq!(config.globals.len(), 2);
+    assert!(matches!(config.globals["b"], crate::GlobalValue::Writable));
+    assert_eq!(config.ignore.len(), IGNORE_PATTERNS.len() + 2);
+    assert_eq!(
+      &config.ignore[IGNORE_PATTERNS.len()..],
+      ["dist/**", "lib/**"]
+    );
+    assert_eq!(
+      config.severity.level_of("eslint(no-empty)"),
+      Some(SeverityLevel::Warn)
+    );
+    assert_eq!(
+      config.severity.level_of("eslint(no-debugger)"),
+      Some(SeverityLevel::Off)
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn should_reject_circular_extends() {
+    let dir = project(
+      "sfconfig-cycle",
+      &[
+        ("a.json", r#"{ "extends": ["./b.json"] }"#),
+        ("b.json", r#"{ "extends": ["./a.json"] }"#),
+        (".sfconfig/spec.json", r#"{ "extends": ["../a.json"] }"#),
+      ],
+    );
+
+    let error = Sfconfig::parse(dir.join(".sfconfig/spec.json")).unwrap_err();
+
+    assert!(error.to_string().contains("Circular extends"));
 
-    Ok(Self::default())
+    fs::remove_dir_all(&dir).unwrap();
   }
 }
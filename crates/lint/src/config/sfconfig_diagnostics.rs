@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+use doctor_core::{Diagnostic, DiagnosticCode};
+use miette::{LabeledSpan, diagnostic};
+
+use super::SFCONFIG_PRESETS;
+
+pub const SFCONFIG_VALIDATOR: &str = "sfconfig";
+
+/// Rules of every code [`SfconfigDiagnosticFactory`] can produce
+const RULES: [&str; 5] = [
+  "invalid-extends",
+  "invalid-glob",
+  "invalid-json",
+  "invalid-value",
+  "unknown-key",
+];
+
+pub struct SfconfigDiagnosticFactory;
+
+impl SfconfigDiagnosticFactory {
+  pub fn codes() -> Vec<DiagnosticCode> {
+    RULES
+      .iter()
+      .map(|rule| DiagnosticCode::shined(SFCONFIG_VALIDATOR, *rule))
+      .collect()
+  }
+
+  pub fn at_invalid_json(span: Range<usize>, reason: &str) -> Diagnostic {
+    let code = DiagnosticCode::shined(SFCONFIG_VALIDATOR, "invalid-json");
+    let labels = vec![LabeledSpan::at(span, reason)];
+
+    Diagnostic::from(diagnostic!(
+      severity = miette::Severity::Error,
+      code = code.to_string(),
+      help = "spec.json must be plain JSON, without comments or trailing commas",
+      labels = labels,
+      "spec.json is not valid JSON",
+    ))
+  }
+
+  pub fn at_unknown_key(span: Range<usize>, key: &str, known: &[&str]) -> Diagnostic {
+    let code = DiagnosticCode::shined(SFCONFIG_VALIDATOR, "unknown-key");
+    let help = format!("Known keys here: {}", known.join(", "));
+    let labels = vec![LabeledSpan::underline(span)];
+
+    Diagnostic::from(diagnostic!(
+      severity = miette::Severity::Error,
+      code = code.to_string(),
+      help = help,
+      labels = labels,
+      "Unknown key `{}`",
+      key
+    ))
+  }
+
+  pub fn at_invalid_value(span: Range<usize>, path: &str, reason: &str) -> Diagnostic {
+    let code = DiagnosticCode::shined(SFCONFIG_VALIDATOR, "invalid-value");
+    let labels = vec![LabeledSpan::at(span, reason)];
+
+    Diagnostic::from(diagnostic!(
+      severity = miette::Severity::Error,
+      code = code.to_string(),
+      labels = labels,
+      "Invalid value of `{}`",
+      path
+    ))
+  }
+
+  pub fn at_invalid_extends(span: Range<usize>, entry: &str, reason: &str) -> Diagnostic {
+    let code = DiagnosticCode::shined(SFCONFIG_VALIDATOR, "invalid-extends");
+    let help = format!(
+      "Use a built-in preset ({}) or a path relative to spec.json",
+      SFCONFIG_PRESETS.join(", ")
+    );
+    let labels = vec![LabeledSpan::at(span, reason)];
+
+    Diagnostic::from(diagnostic!(
+      severity = miette::Severity::Error,
+      code = code.to_string(),
+      help = help,
+      labels = labels,
+      "Can not extend `{}`",
+      entry
+    ))
+  }
+
+  pub fn at_invalid_glob(span: Range<usize>, pattern: &str, reason: &str) -> Diagnostic {
+    let code = DiagnosticCode::shined(SFCONFIG_VALIDATOR, "invalid-glob");
+    let labels = vec![LabeledSpan::at(span, reason)];
+
+    Diagnostic::from(diagnostic!(
+      severity = miette::Severity::Error,
+      code = code.to_string(),
+      help = "Patterns of `ignore` use gitignore glob syntax",
+      labels = labels,
+      "Invalid ignore pattern `{}`",
+      pattern
+    ))
+  }
+}
@@ -0,0 +1,165 @@
+use std::{
+  fs::read_to_string,
+  path::{Path, PathBuf},
+};
+
+use doctor_core::{IGNORE_PATTERNS, ValidatorError};
+use serde_json::{Map, Value, json};
+
+/// Prefix of the built-in presets, any other `extends` entry is a file path
+pub const PRESET_PREFIX: &str = "doctor:";
+
+/// Names of the built-in presets `extends` accepts
+pub const SFCONFIG_PRESETS: [&str; 2] = ["doctor:recommended", "doctor:relaxed"];
+
+fn preset(name: &str) -> Option<Value> {
+  match name {
+    "doctor:recommended" => Some(json!({ "ignore": IGNORE_PATTERNS })),
+    // 老项目逐步接入时先把 lint 降为警告
+    "doctor:relaxed" => Some(json!({
+      "extends": ["doctor:recommended"],
+      "severity": { "eslint(*)": "warn" }
+    })),
+    _ => None,
+  }
+}
+
+fn invalid(message: String) -> ValidatorError {
+  ValidatorError::Unknown(message.into())
+}
+
+/// Merge `overlay` into `base`, `overlay` wins
+///
+/// Objects are merged key by key, so `globals`, `severity` and `policy` combine across the
+/// chain. `ignore` lists are appended to each other, every other value is replaced.
+pub(crate) fn merge(base: &mut Map<String, Value>, overlay: Map<String, Value>) {
+  for (key, value) in overlay {
+    match (base.get_mut(&key), value) {
+      (Some(Value::Array(base)), Value::Array(items)) if key == "ignore" => {
+        for item in items {
+          if !base.contains(&item) {
+            base.push(item);
+          }
+        }
+      }
+      (Some(Value::Object(base)), Value::Object(value)) => merge(base, value),
+      (_, value) => {
+        base.insert(key, value);
+      }
+    }
+  }
+}
+
+/// Resolves the `extends` chain of one spec.json into a single JSON object
+pub(crate) struct ExtendsResolver {
+  /// Files and presets being resolved, outermost first
+  stack: Vec<String>,
+}
+
+impl ExtendsResolver {
+  /// Starts from the spec.json at `path`
+  pub(crate) fn new(path: &Path) -> Self {
+    Self {
+      stack: vec![Self::id_of(path)],
+    }
+  }
+
+  fn id_of(path: &Path) -> String {
+    path
+      .canonicalize()
+      .unwrap_or_else(|_| path.to_path_buf())
+      .display()
+      .to_string()
+  }
+
+  /// `config` with everything it extends merged in, `dir` is where its relative entries start
+  pub(crate) fn resolve(&mut self, config: Value, dir: &Path) -> Result<Value, ValidatorError> {
+    let Value::Object(mut own) = config else {
+      return Err(invalid("A config must be a JSON object".to_string()));
+    };
+
+    let extends = match own.remove("extends") {
+      None => vec![],
+      Some(Value::Array(entries)) => entries
+        .into_iter()
+        .map(|entry| match entry {
+          Value::String(entry) => Ok(entry),
+          entry => Err(invalid(format!("`extends` entry {entry} is not a string"))),
+        })
+        .collect::<Result<Vec<_>, _>>()?,
+      Some(_) => return Err(invalid("`extends` must be a list".to_string())),
+    };
+    // 只对当前文件有意义
+    own.remove("$schema");
+
+    let mut merged = Map::new();
+    for entry in extends {
+      if let Value::Object(parent) = self.resolve_entry(&entry, dir)? {
+        merge(&mut merged, parent);
+      }
+    }
+    merge(&mut merged, own);
+
+    Ok(Value::Object(merged))
+  }
+
+  /// One `extends` entry resolved, a preset name or a path relative to `dir`
+  pub(crate) fn resolve_entry(&mut self, entry: &str, dir: &Path) -> Result<Value, ValidatorError> {
+    let (id, config, dir) = if entry.starts_with(PRESET_PREFIX) {
+      let config = preset(entry).ok_or_else(|| {
+        invalid(format!(
+          "Unknown preset `{entry}`, available presets: {}",
+          SFCONFIG_PRESETS.join(", ")
+        ))
+      })?;
+      (entry.to_string(), config, dir.to_path_buf())
+    } else {
+      let path = dir.join(entry);
+      let source = read_to_string(&path)
+        .map_err(|e| invalid(format!("Can not read `{entry}` ({}): {e}", path.display())))?;
+      let config = serde_json::from_str(&source)
+        .map_err(|e| invalid(format!("Invalid config {}: {e}", path.display())))?;
+      let dir = path.parent().map_or_else(PathBuf::new, Path::to_path_buf);
+      (Self::id_of(&path), config, dir)
+    };
+
+    if let Some(start) = self.stack.iter().position(|item| *item == id) {
+      let chain = self.stack[start..]
+        .iter()
+        .chain([&id])
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+      return Err(invalid(format!("Circular extends: {}", chain.join(" -> "))));
+    }
+
+    self.stack.push(id);
+    let resolved = self.resolve(config, &dir);
+    self.stack.pop();
+    resolved
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use oxc_linter::Oxlintrc;
+
+  use super::*;
+  use crate::{Sfconfig, common::severity::enabled_rule_codes, inner::Category20250601Inner};
+
+  #[test]
+  fn should_keep_off_rules_off_in_relaxed_preset() {
+    let resolved = ExtendsResolver::new(Path::new("spec.json"))
+      .resolve(json!({ "extends": ["doctor:relaxed"] }), Path::new("."))
+      .unwrap();
+    let sfconfig = serde_json::from_value::<Sfconfig>(resolved).unwrap();
+
+    let oxlintrc: Oxlintrc = Category20250601Inner::builder()
+      .severity(sfconfig.severity)
+      .build()
+      .into();
+    let codes = enabled_rule_codes(&serde_json::to_value(&oxlintrc).unwrap());
+
+    assert!(codes.iter().any(|code| code == "eslint(no-empty)"));
+    assert!(!codes.iter().any(|code| code == "eslint(no-fallthrough)"));
+  }
+}
@@ -0,0 +1,498 @@
+use std::{
+  fs::read_to_string,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+};
+
+use doctor_core::{Diagnostic, Messages, ValidatorError, ValidatorMeta, traits::Validator};
+use ignore::overrides::OverrideBuilder;
+use jsonc_parser::{
+  CollectOptions, ParseOptions,
+  ast::{StringLit, Value},
+  common::Ranged,
+  parse_to_ast,
+};
+use jsonschema::error::ValidationErrorKind;
+use serde_json::Value as JsonValue;
+use typed_builder::TypedBuilder;
+
+use super::{ExtendsResolver, SFCONFIG_VALIDATOR, Sfconfig, SfconfigDiagnosticFactory};
+
+/// Checks `.sfconfig/spec.json` against the JSON Schema of [`Sfconfig`]
+///
+/// Unlike [`Sfconfig::parse`] every problem is reported, each pointing into the file. A
+/// missing spec.json is fine, the default config is used then.
+///
+/// # Example
+///
+/// ```rust
+/// use doctor_lint::SfconfigValidator;
+/// use doctor_core::traits::Validator;
+///
+/// let validator = SfconfigValidator::builder()
+///   .config_path("./fixtures/not-found/spec.json")
+///   .build();
+/// assert!(validator.validate().unwrap().is_empty());
+/// ```
+#[derive(Debug, TypedBuilder)]
+pub struct SfconfigValidator {
+  #[builder(setter(into))]
+  config_path: PathBuf,
+}
+
+impl Validator for SfconfigValidator {
+  fn meta(&self) -> ValidatorMeta {
+    ValidatorMeta::builder()
+      .id(SFCONFIG_VALIDATOR)
+      .description(
+        "Reports unknown keys, invalid values, broken extends and invalid ignore globs in .sfconfig/spec.json",
+      )
+      .codes(SfconfigDiagnosticFactory::codes())
+      .fixable(false)
+      .build()
+  }
+
+  fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+    let source_code = match read_to_string(&self.config_path) {
+      Ok(source_code) => source_code,
+      Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+      Err(e) => return Err(e.into()),
+    };
+
+    let diagnostics = check_sfconfig(&source_code, &self.config_path)?;
+
+    Ok(vec![
+      Messages::builder()
+        .validator(SFCONFIG_VALIDATOR)
+        .source_code(source_code)
+        .source_path(self.config_path.display().to_string())
+        .diagnostics(diagnostics)
+        .build(),
+    ])
+  }
+
+  fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
+    self.validate()
+  }
+}
+
+/// Diagnostics of the spec.json at `path` with `source`
+///
+/// `extends` entries are resolved relative to `path`, the files they point at are checked
+/// as far as resolving them goes. Fails only when the schema of [`Sfconfig`] does not compile.
+pub fn check_sfconfig(source: &str, path: &Path) -> Result<Vec<Diagnostic>, ValidatorError> {
+  // serde_json 不支持注释和尾逗号，这里保持一致
+  let options = ParseOptions {
+    allow_comments: false,
+    allow_loose_object_property_names: false,
+    allow_trailing_commas: false,
+    ..Default::default()
+  };
+
+  let ast = match parse_to_ast(source, &CollectOptions::default(), &options) {
+    Ok(result) => result.value,
+    Err(e) => {
+      let range = e.range();
+      return Ok(vec![SfconfigDiagnosticFactory::at_invalid_json(
+        range.start..range.end,
+        &e.kind().to_string(),
+      )]);
+    }
+  };
+
+  let Some(value) = ast else {
+    return Ok(vec![SfconfigDiagnosticFactory::at_invalid_json(
+      0..source.len(),
+      "the file is empty",
+    )]);
+  };
+
+  let mut diagnostics = vec![];
+  // 能解析成 AST 的源码 serde_json 一定也能解析，重复的 key 留给下面的 serde 报告
+  if let Ok(instance) = serde_json::from_str::<JsonValue>(source) {
+    diagnostics = check_schema(&Sfconfig::json_schema(), &instance, &value)?;
+  }
+  check_extends(&value, path, &mut diagnostics);
+  check_ignore(&value, path, &mut diagnostics);
+
+  // 比如重复的 key，结构上没问题但 serde 不接受
+  let reparsed = diagnostics
+    .is_empty()
+    .then(|| serde_json::from_str::<Sfconfig>(source));
+  if let Some(Err(e)) = reparsed {
+    let offset = offset_of(source, e.line(), e.column());
+    diagnostics.push(SfconfigDiagnosticFactory::at_invalid_json(
+      offset..offset,
+      &e.to_string(),
+    ));
+  }
+
+  Ok(diagnostics)
+}
+
+/// Byte offset of a 1-based `line` and `column` reported by serde_json
+fn offset_of(source: &str, line: usize, column: usize) -> usize {
+  let line_start = source
+    .split_inclusive('\n')
+    .take(line.saturating_sub(1))
+    .map(str::len)
+    .sum::<usize>();
+  (line_start + column.saturating_sub(1)).min(source.len())
+}
+
+/// The string elements of the top level array `key` of `value`
+fn string_elements<'a, 'b>(value: &'b Value<'a>, key: &str) -> Vec<&'b StringLit<'a>> {
+  value
+    .as_object()
+    .and_then(|object| object.get(key))
+    .and_then(|prop| prop.value.as_array())
+    .map(|array| {
+      array
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+          Value::StringLit(lit) => Some(lit),
+          _ => None,
+        })
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+fn check_extends(value: &Value, path: &Path, diagnostics: &mut Vec<Diagnostic>) {
+  let dir = path.parent().unwrap_or(Path::new(""));
+
+  for entry in string_elements(value, "extends") {
+    let resolved = ExtendsResolver::new(path)
+      .resolve_entry(&entry.value, dir)
+      .and_then(|config| {
+        serde_json::from_value::<Sfconfig>(config).map_err(|e| ValidatorError::Unknown(Box::new(e)))
+      });
+
+    if let Err(e) = resolved {
+      let range = entry.range();
+      diagnostics.push(SfconfigDiagnosticFactory::at_invalid_extends(
+        range.start..range.end,
+        &entry.value,
+        &e.to_string(),
+      ));
+    }
+  }
+}
+
+fn check_ignore(value: &Value, path: &Path, diagnostics: &mut Vec<Diagnostic>) {
+  // .sfconfig/spec.json 的上上级才是项目根目录
+  let root = path
+    .parent()
+    .and_then(|dir| dir.parent())
+    .unwrap_or(Path::new("."));
+
+  for pattern in string_elements(value, "ignore") {
+    // 与 WalkParallelJs 的写法一致，ignore 以取反的 override 生效
+    if let Err(e) = OverrideBuilder::new(root).add(&format!("!{}", pattern.value)) {
+      let range = pattern.range();
+      diagnostics.push(SfconfigDiagnosticFactory::at_invalid_glob(
+        range.start..range.end,
+        &pattern.value,
+        &e.to_string(),
+      ));
+    }
+  }
+}
+
+/// The errors of the JSON Schema of [`Sfconfig`] for `instance`, each pointing at its place
+/// in `value`, in the order of the file
+fn check_schema(
+  schema: &JsonValue,
+  instance: &JsonValue,
+  value: &Value,
+) -> Result<Vec<Diagnostic>, ValidatorError> {
+  let mut diagnostics = vec![];
+  collect_errors(schema, schema, instance, value, &[], &mut diagnostics)?;
+
+  // 错误的顺序取决于 schema 里关键字的顺序，按在文件里的位置报告
+  diagnostics.sort_by_key(|diagnostic| {
+    diagnostic
+      .labels
+      .as_ref()
+      .and_then(|labels| labels.first())
+      .map(|label| label.offset())
+  });
+  Ok(diagnostics)
+}
+
+/// Validates `instance` at `prefix` against `schema`, the root or an alternative in it
+///
+/// A value that fits none of the `anyOf` / `oneOf` alternatives is checked against the one of
+/// its type if there is only one, so a typo in an optional object is reported as that and not
+/// as the whole object being invalid.
+fn collect_errors(
+  root: &JsonValue,
+  schema: &JsonValue,
+  instance: &JsonValue,
+  value: &Value,
+  prefix: &[String],
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Result<(), ValidatorError> {
+  let validator =
+    jsonschema::validator_for(schema).map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
+
+  for error in validator.iter_errors(instance) {
+    let relative = segments(error.instance_path.as_str());
+    let Some(node) = node_at(value, &relative) else {
+      continue;
+    };
+    let pointer = prefix.iter().chain(&relative).cloned().collect::<Vec<_>>();
+    let schema_path = segments(error.schema_path.as_str());
+
+    match &error.kind {
+      ValidationErrorKind::AdditionalProperties { unexpected } => {
+        let Some(object) = node.as_object() else {
+          continue;
+        };
+        let known = schema_path
+          .split_last()
+          .and_then(|(_, parent)| schema_at(root, schema, parent))
+          .and_then(|parent| parent.get("properties")?.as_object())
+          .map(|properties| properties.keys().map(String::as_str).collect::<Vec<_>>())
+          .unwrap_or_default();
+
+        for key in unexpected {
+          if let Some(prop) = object.get(key) {
+            let range = prop.name.range();
+            diagnostics.push(SfconfigDiagnosticFactory::at_unknown_key(
+              range.start..range.end,
+              key,
+              &known,
+            ));
+          }
+        }
+        continue;
+      }
+      ValidationErrorKind::AnyOf | ValidationErrorKind::OneOfNotValid => {
+        let alternatives = schema_at(root, schema, &schema_path)
+          .and_then(JsonValue::as_array)
+          .map(|alternatives| {
+            alternatives
+              .iter()
+              .filter(|alternative| allows_type(root, alternative, &error.instance))
+              .collect::<Vec<_>>()
+          })
+          .unwrap_or_default();
+
+        if let [alternative] = alternatives[..] {
+          // 单独校验这个分支时 $ref 仍要能找到根上的 $defs
+          let mut alternative = alternative.clone();
+          if let (Some(object), Some(defs)) = (alternative.as_object_mut(), root.get("$defs")) {
+            object.insert("$defs".to_string(), defs.clone());
+          }
+          collect_errors(
+            root,
+            &alternative,
+            &error.instance,
+            node,
+            &pointer,
+            diagnostics,
+          )?;
+          continue;
+        }
+      }
+      _ => {}
+    }
+
+    let range = node.range();
+    diagnostics.push(SfconfigDiagnosticFactory::at_invalid_value(
+      range.start..range.end,
+      &dotted(&pointer),
+      &error.to_string(),
+    ));
+  }
+
+  Ok(())
+}
+
+/// The unescaped segments of a JSON pointer like `/policy/packageJson`
+fn segments(pointer: &str) -> Vec<String> {
+  pointer
+    .split('/')
+    .skip(1)
+    .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+    .collect()
+}
+
+/// The value of the AST at the instance `pointer`
+fn node_at<'a, 'b>(value: &'b Value<'a>, pointer: &[String]) -> Option<&'b Value<'a>> {
+  let Some((segment, rest)) = pointer.split_first() else {
+    return Some(value);
+  };
+
+  let child = match value {
+    Value::Object(object) => &object.get(segment)?.value,
+    Value::Array(array) => array.elements.get(segment.parse::<usize>().ok()?)?,
+    _ => return None,
+  };
+  node_at(child, rest)
+}
+
+/// The subschema of `schema` at `schema_path`
+///
+/// schemars only refers into `$defs` of the root, a `$ref` segment continues there.
+fn schema_at<'s>(
+  root: &'s JsonValue,
+  schema: &'s JsonValue,
+  schema_path: &[String],
+) -> Option<&'s JsonValue> {
+  schema_path.iter().try_fold(schema, |current, segment| {
+    if segment == "$ref" {
+      let name = current.get("$ref")?.as_str()?.strip_prefix("#/$defs/")?;
+      return root.get("$defs")?.get(name);
+    }
+    match current {
+      JsonValue::Array(items) => items.get(segment.parse::<usize>().ok()?),
+      _ => current.get(segment),
+    }
+  })
+}
+
+/// Follows the `$ref` of `schema` into the `$defs` of `root`, `schema` itself without one
+fn resolve<'s>(root: &'s JsonValue, schema: &'s JsonValue) -> Option<&'s JsonValue> {
+  match schema.get("$ref").and_then(JsonValue::as_str) {
+    Some(reference) => {
+      let name = reference.strip_prefix("#/$defs/")?;
+      resolve(root, root.get("$defs")?.get(name)?)
+    }
+    None => Some(schema),
+  }
+}
+
+/// Whether `schema` allows values of the JSON type of `instance`, without a `type` any
+fn allows_type(root: &JsonValue, schema: &JsonValue, instance: &JsonValue) -> bool {
+  let actual = match instance {
+    JsonValue::Null => "null",
+    JsonValue::Bool(_) => "boolean",
+    JsonValue::Number(_) => "number",
+    JsonValue::String(_) => "string",
+    JsonValue::Array(_) => "array",
+    JsonValue::Object(_) => "object",
+  };
+  let matches = |expected: &JsonValue| {
+    expected
+      .as_str()
+      .is_some_and(|expected| expected == actual || (expected == "integer" && actual == "number"))
+  };
+
+  match resolve(root, schema).and_then(|schema| schema.get("type")) {
+    Some(JsonValue::Array(types)) => types.iter().any(matches),
+    Some(expected) => matches(expected),
+    None => true,
+  }
+}
+
+/// `globals.window` or `ignore[0]` for an instance pointer, `spec.json` for the root
+fn dotted(pointer: &[String]) -> String {
+  let mut path = String::new();
+  for segment in pointer {
+    if segment.parse::<usize>().is_ok() {
+      path.push_str(&format!("[{segment}]"));
+    } else {
+      if !path.is_empty() {
+        path.push('.');
+      }
+      path.push_str(segment);
+    }
+  }
+
+  if path.is_empty() {
+    "spec.json".to_string()
+  } else {
+    path
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn codes(source: &str) -> Vec<String> {
+    check_sfconfig(source, Path::new(".sfconfig/spec.json"))
+      .unwrap()
+      .into_iter()
+      .filter_map(|diagnostic| diagnostic.code.clone())
+      .collect()
+  }
+
+  #[test]
+  fn should_accept_valid_config() {
+    let source = r#"{
+  "$schema": "./spec.schema.json",
+  "globals": { "window": "readonly" },
+  "ignore": ["dist/**"],
+  "severity": { "eslint(no-debugger)": "warn" },
+  "policy": { "nodeVersions": ["^22.0.0"], "packageJson": { "private": "off" } }
+}"#;
+
+    assert!(codes(source).is_empty());
+  }
+
+  #[test]
+  fn should_report_problems_with_labels() {
+    let source = r#"{
+  "globals": { "window": "readwrite" },
+  "ignore": ["src/[a-"],
+  "policy": { "packageJson": { "privat": true } },
+  "lint": {}
+}"#;
+
+    let diagnostics = check_sfconfig(source, Path::new(".sfconfig/spec.json")).unwrap();
+    let codes = diagnostics
+      .iter()
+      .filter_map(|diagnostic| diagnostic.code.as_deref())
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      codes,
+      vec![
+        "shined(sfconfig:invalid-value)",
+        "shined(sfconfig:unknown-key)",
+        "shined(sfconfig:unknown-key)",
+        "shined(sfconfig:invalid-glob)",
+      ]
+    );
+
+    let label = |index: usize| {
+      let label = &diagnostics[index].labels.as_ref().unwrap()[0];
+      &source[label.offset()..label.offset() + label.len()]
+    };
+    assert_eq!(label(0), r#""readwrite""#);
+    assert_eq!(label(1), r#""privat""#);
+    // 可选的 packageJson 也按它自己的 schema 报告未知的 key
+    assert!(diagnostics[1].help.as_ref().unwrap().contains("private"));
+    assert_eq!(label(2), r#""lint""#);
+    assert_eq!(label(3), r#""src/[a-""#);
+  }
+
+  #[test]
+  fn should_report_broken_extends() {
+    let source = r#"{ "extends": ["doctor:relaxed", "doctor:nope", "./missing.json"] }"#;
+
+    assert_eq!(
+      codes(source),
+      vec![
+        "shined(sfconfig:invalid-extends)",
+        "shined(sfconfig:invalid-extends)",
+      ]
+    );
+  }
+
+  #[test]
+  fn should_report_invalid_json() {
+    assert_eq!(
+      codes("{ \"ignore\": [], }"),
+      vec!["shined(sfconfig:invalid-json)"]
+    );
+    assert_eq!(
+      codes(r#"{ "ignore": [], "ignore": [] }"#),
+      vec!["shined(sfconfig:invalid-json)"]
+    );
+  }
+}
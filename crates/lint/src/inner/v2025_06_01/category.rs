@@ -1,11 +1,11 @@
-use doctor_core::Ignore;
+use doctor_core::{Ignore, SeverityOverrides};
 use oxc_linter::Oxlintrc;
 use serde_json::json;
 use typed_builder::TypedBuilder;
 
 use crate::{
   Globals, LintMode,
-  common::environments::EnvironmentFlags,
+  common::{environments::EnvironmentFlags, severity::apply_rule_severity},
   config::{ReactConfig, TypescriptConfig},
   ext::CategoryGetter,
 };
@@ -24,6 +24,8 @@ pub struct Category20250601Inner {
   pub globals: Globals,
   #[builder(default = Ignore::default())]
   pub ignore: Ignore,
+  #[builder(default = SeverityOverrides::default())]
+  pub severity: SeverityOverrides,
 }
 
 impl Default for Category20250601Inner {
@@ -40,7 +42,7 @@ impl Into<Oxlintrc> for Category20250601Inner {
 
 impl CategoryGetter for Category20250601Inner {
   fn get_config(&self) -> Oxlintrc {
-    let config = json!({
+    let mut config = json!({
       "plugins": ["eslint", "typescript", "unicorn", "react", "oxc"],
       "categories": {
         "correctness": "off",
@@ -176,6 +178,8 @@ impl CategoryGetter for Category20250601Inner {
       "ignorePatterns":self.ignore.iter().map(|s| s.as_str()).collect::<Vec<&str>>()
     });
 
+    apply_rule_severity(&mut config, &self.severity);
+
     let config = serde_json::from_value::<Oxlintrc>(config).unwrap();
 
     config
@@ -6,12 +6,15 @@ pub use common::{
   FileDiagnostic,
   environments::{Environment, EnvironmentFlags},
   lint_mode::LintMode,
+  severity::rule_code,
 };
 
 pub mod config;
 pub mod ext;
 pub mod inner;
+mod pipeline;
 mod validator;
 
 pub use config::*;
+pub use pipeline::SourcePipeline;
 pub use validator::*;
@@ -0,0 +1,157 @@
+use std::sync::{Arc, OnceLock};
+
+use doctor_core::{Messages, Patch, ValidatorError, ValidatorMeta, traits::Validator};
+use doctor_syntax::{SyntaxSource, syntax_messages};
+use oxc_linter::FixKind;
+
+use crate::LintValidator;
+
+#[derive(Debug)]
+struct Files {
+  syntax: Vec<Messages>,
+  lint: Vec<Messages>,
+}
+
+#[derive(Debug)]
+struct Inner {
+  lint: LintValidator,
+  // 失败时只保留错误信息，ValidatorError 不能 clone
+  files: OnceLock<Result<Files, String>>,
+}
+
+/// A single read and parse pass over the project, shared by the syntax and lint validators
+///
+/// The first of them to run walks the tree, the other one reuses the result. Parse errors are
+/// only reported by syntax, a file is still linted unless the parser gave up on it.
+///
+/// The handle is cheap to clone, use it as the lint validator and as the
+/// [`SyntaxSource`] of a `SyntaxValidator`.
+#[derive(Debug, Clone)]
+pub struct SourcePipeline {
+  inner: Arc<Inner>,
+}
+
+impl SourcePipeline {
+  pub fn new(lint: LintValidator) -> Self {
+    Self {
+      inner: Arc::new(Inner {
+        lint,
+        files: OnceLock::new(),
+      }),
+    }
+  }
+
+  /// Run the shared pass now instead of in the first validator that needs it
+  ///
+  /// Call it before running both validators on a thread pool, a pool thread that waits for the
+  /// pass could otherwise be the one the pass needs.
+  pub fn prepare(&self) -> Result<(), ValidatorError> {
+    self.files().map(|_| ())
+  }
+
+  fn files(&self) -> Result<&Files, ValidatorError> {
+    let files = self.inner.files.get_or_init(|| {
+      let files = self
+        .inner
+        .lint
+        .process_all(FixKind::None)
+        .map_err(|e| e.to_string())?;
+
+      let mut syntax = Vec::with_capacity(files.len());
+      let mut lint = Vec::with_capacity(files.len());
+      for file in files {
+        syntax.push(syntax_messages(
+          file.messages.source_path.clone(),
+          file.messages.source_code.clone(),
+          file.syntax,
+        ));
+        lint.push(file.messages);
+      }
+
+      Ok(Files { syntax, lint })
+    });
+
+    files
+      .as_ref()
+      .map_err(|e| ValidatorError::Unknown(e.clone().into()))
+  }
+}
+
+impl SyntaxSource for SourcePipeline {
+  fn syntax_messages(&self) -> Result<Vec<Messages>, ValidatorError> {
+    Ok(self.files()?.syntax.clone())
+  }
+}
+
+impl Validator for SourcePipeline {
+  fn meta(&self) -> ValidatorMeta {
+    self.inner.lint.meta()
+  }
+
+  fn prepare(&self) -> Result<(), ValidatorError> {
+    SourcePipeline::prepare(self)
+  }
+
+  fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+    Ok(self.files()?.lint.clone())
+  }
+
+  /// Fixing needs a linter that produces fixes, so it does not use the shared pass
+  fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
+    self.inner.lint.fix()
+  }
+
+  fn fixes(&self) -> Result<Vec<Messages>, ValidatorError> {
+    self.inner.lint.fixes()
+  }
+
+  fn fix_dry_run(&self) -> Result<Patch, ValidatorError> {
+    self.inner.lint.fix_dry_run()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::*;
+  use crate::OxlintrcBuilder;
+
+  fn messages_of<'a>(messages: &'a [Messages], file: &str) -> &'a Messages {
+    messages
+      .iter()
+      .find(|m| m.source_path.ends_with(file))
+      .unwrap()
+  }
+
+  #[test]
+  fn should_report_parse_errors_only_as_syntax_errors() {
+    let lint = LintValidator::builder()
+      .cwd(PathBuf::from("./fixtures/pipeline"))
+      .oxlintrc(OxlintrcBuilder::default().build())
+      .build();
+    let pipeline = SourcePipeline::new(lint);
+
+    let syntax = pipeline.syntax_messages().unwrap();
+    let lint = pipeline.validate().unwrap();
+
+    assert_eq!(syntax.len(), 3);
+    assert!(!messages_of(&syntax, "broken.js").is_empty());
+    assert!(messages_of(&syntax, "dupe.js").is_empty());
+
+    // 解析器放弃的文件没法 lint
+    assert!(messages_of(&lint, "broken.js").is_empty());
+    assert!(!messages_of(&lint, "dupe.js").is_empty());
+
+    // 能恢复的语法错误不影响 lint，也不会被 lint 再报一次
+    let recoverable = messages_of(&syntax, "recoverable.js");
+    assert!(!recoverable.is_empty());
+    let linted = messages_of(&lint, "recoverable.js");
+    assert!(!linted.is_empty());
+    assert!(linted.iter().all(|diagnostic| {
+      recoverable
+        .iter()
+        .all(|syntax| syntax.message != diagnostic.message)
+    }));
+  }
+}
@@ -1,30 +1,50 @@
 use std::{
-  fs,
   path::{Path, PathBuf},
   sync::Arc,
 };
 
-use doctor_core::{Ignore, Messages, ValidatorError, traits::Validator};
+use doctor_core::{
+  CachedDiagnostic, ChangedFiles, Diagnostic, DiagnosticCode, Fix, Ignore, Messages, Patch,
+  ResultCache, TextEdit, ValidatorError, ValidatorMeta, hack_source_type_from_path,
+  traits::Validator,
+};
+use doctor_syntax::to_syntax_diagnostics;
 use doctor_walk::{WalkError, WalkParallelJs};
 use oxc::{
   allocator::Allocator,
-  diagnostics::{GraphicalReportHandler, NamedSource},
+  diagnostics::{GraphicalReportHandler, NamedSource, OxcDiagnostic},
   parser::Parser,
   semantic::SemanticBuilder,
-  span::SourceType,
 };
 use oxc_linter::{
   AllowWarnDeny, ConfigStore, ConfigStoreBuilder, ContextSubHost, ExternalPluginStore, FixKind,
   FrameworkFlags, LintOptions, Linter, Message, Oxlintrc, PossibleFixes,
 };
 use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
 use crate::{
   FileDiagnostic,
-  common::{error::LintError, named_source},
+  common::{error::LintError, named_source, severity::enabled_rule_codes},
 };
 
+pub const VALIDATOR: &str = "lint";
+
+/// A file after the read, parse and lint pass
+pub(crate) struct LintedFile {
+  /// Syntax diagnostics, a file is only left unlinted when the parser gave up on it
+  pub syntax: Vec<Diagnostic>,
+  pub messages: Messages,
+}
+
+/// What the result cache keeps for a file
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+  syntax: Vec<CachedDiagnostic>,
+  lint: Vec<CachedDiagnostic>,
+}
+
 #[derive(Debug, Clone, TypedBuilder)]
 pub struct LintValidator {
   cwd: PathBuf,
@@ -35,6 +55,14 @@ pub struct LintValidator {
 
   #[builder(default = Ignore::default())]
   pub ignore: Ignore,
+
+  /// Where to cache results, unchanged files are not parsed and linted again
+  #[builder(default = None)]
+  cache_dir: Option<PathBuf>,
+
+  /// Only lint these files instead of every file under `cwd`
+  #[builder(default = None)]
+  files: Option<ChangedFiles>,
 }
 
 impl LintValidator {
@@ -62,20 +90,39 @@ impl LintValidator {
     Ok(linter)
   }
 
+  /// Only results without fixes are cached, a fixing run always lints
+  fn cache(&self, fix_kind: FixKind) -> Option<ResultCache> {
+    if fix_kind != FixKind::None {
+      return None;
+    }
+    let dir = self.cache_dir.as_ref()?;
+    // 配置、doctor 或 oxc 的版本变了，旧的缓存全部失效
+    let config = serde_json::to_string(&self.oxlintrc).ok()?;
+    let salt = format!(
+      "doctor_lint@{}\noxc_linter@{}\n{}",
+      env!("CARGO_PKG_VERSION"),
+      env!("DOCTOR_OXC_LINTER"),
+      config
+    );
+    Some(ResultCache::new(dir, salt))
+  }
+
   fn process_file(
     &self,
     linter: &Linter,
-    path: &Path,
-  ) -> Result<(named_source::PathWithSource, Vec<Message>), WalkError> {
-    let named_source = named_source::PathWithSource::try_from(path)?;
-    let source_type = SourceType::from_path(path).map_err(|e| WalkError::Unknown(e.to_string()))?;
+    named_source: &named_source::PathWithSource,
+  ) -> (Vec<OxcDiagnostic>, Vec<Message>) {
+    let path = Path::new(&named_source.file_path);
+    // 和 syntax 用同一套 source type 推断
+    let source_type = hack_source_type_from_path(path);
 
     let allocator = Allocator::default();
     let parser = Parser::new(&allocator, &named_source.source_code, source_type);
     let parser_return = parser.parse();
 
+    // 解析器放弃的文件没有可用的 AST，只报语法错误；能恢复的错误照常 lint
     if parser_return.panicked {
-      return Ok((named_source, vec![]));
+      return (parser_return.errors, vec![]);
     }
 
     let program = allocator.alloc(&parser_return.program);
@@ -86,119 +133,162 @@ impl LintValidator {
 
     let semantic = semantic_builder_return.semantic;
     let module_record = Arc::new(oxc_linter::ModuleRecord::new(
-      Path::new(&named_source.file_path),
+      path,
       &parser_return.module_record,
       &semantic,
     ));
 
     let context_sub_hosts = ContextSubHost::new(semantic, module_record, 0);
-    let messages = linter.run(
-      Path::new(&named_source.file_path),
-      vec![context_sub_hosts],
-      &allocator,
-    );
+    let mut messages = linter.run(path, vec![context_sub_hosts], &allocator);
+    // 语法错误已经由 syntax 报告了
+    messages.retain(|message| !parser_return.errors.contains(&message.error));
 
-    Ok((named_source, messages))
+    (parser_return.errors, messages)
   }
 
-  fn apply_fixes(&self, source_code: &str, messages: &[Message]) -> Option<String> {
-    let mut fixes = Vec::new();
-
-    // 收集所有修复
-    for msg in messages {
-      match &msg.fixes {
-        PossibleFixes::None => {}
-        PossibleFixes::Single(fix) => fixes.push(fix.clone()),
-        PossibleFixes::Multiple(fs) => fixes.extend(fs.iter().cloned()),
-      }
-    }
-
-    if fixes.is_empty() {
-      return None; // 没有修复
-    }
+  /// Convert oxc fixes into a core [`Fix`], only the first of multiple suggestions is kept
+  fn to_fix(fixes: &PossibleFixes) -> Option<Fix> {
+    let fix = match fixes {
+      PossibleFixes::None => return None,
+      PossibleFixes::Single(fix) => fix,
+      PossibleFixes::Multiple(fixes) => fixes.first()?,
+    };
+
+    let edit = TextEdit::replace(
+      fix.span.start as usize..fix.span.end as usize,
+      fix.content.to_string(),
+    );
 
-    // 按 span.start 倒序排序（从后往前应用，避免位置偏移）
-    fixes.sort_by(|a, b| b.span.start.cmp(&a.span.start));
+    Some(Fix::from(edit))
+  }
 
-    let mut result = source_code.to_string();
-    for fix in fixes {
-      let start = fix.span.start as usize;
-      let end = fix.span.end as usize;
-      result.replace_range(start..end, &fix.content);
+  fn to_messages(named_source: named_source::PathWithSource, messages: Vec<Message>) -> Messages {
+    let mut doctor_messages = Messages::builder()
+      .validator(VALIDATOR)
+      .diagnostics(vec![])
+      .source_code(named_source.source_code)
+      .source_path(named_source.file_path)
+      .build();
+    for msg in messages {
+      let fix = Self::to_fix(&msg.fixes);
+      let diagnostic = doctor_core::Diagnostic::from(msg.error).with_fix(fix);
+      doctor_messages.push(diagnostic);
     }
-
-    Some(result)
+    doctor_messages
   }
 }
 
-impl Validator for LintValidator {
-  fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+impl LintValidator {
+  /// Read, parse and lint every file once
+  pub(crate) fn process_all(&self, fix_kind: FixKind) -> Result<Vec<LintedFile>, ValidatorError> {
     let linter = self
-      .create_linter(FixKind::None)
+      .create_linter(fix_kind)
       .map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
 
     let parallel = WalkParallelJs::builder()
       .cwd(self.cwd.clone())
       .ignore(self.ignore.clone())
+      .files(self.files.clone())
       .build();
 
-    let res: Vec<Result<Messages, WalkError>> = parallel
-      .walk(|path| -> Result<Messages, WalkError> {
-        let (named_source, original_messages) = self.process_file(&linter, &path)?;
-        let mut doctor_messages = Messages::builder()
-          .diagnostics(vec![])
-          .source_code(named_source.source_code.clone())
-          .source_path(named_source.file_path.clone())
-          .build();
-        for msg in original_messages {
-          let diagnostic = doctor_core::Diagnostic::from(msg.error);
-          doctor_messages.push(diagnostic.into());
+    let cache = self.cache(fix_kind);
+
+    let res: Vec<Result<LintedFile, WalkError>> = parallel
+      .walk(|path| -> Result<LintedFile, WalkError> {
+        let named_source = named_source::PathWithSource::try_from(&path)?;
+        // 用相对路径做 key，项目目录搬家后缓存依然有效
+        let key = path
+          .strip_prefix(&self.cwd)
+          .unwrap_or(&path)
+          .to_string_lossy();
+
+        let cached = cache
+          .as_ref()
+          .and_then(|cache| cache.get::<CacheEntry>(&key, &named_source.source_code));
+        if let Some(entry) = cached {
+          let mut messages = Self::to_messages(named_source, vec![]);
+          messages.extend(entry.lint.into_iter().map(Diagnostic::from));
+          return Ok(LintedFile {
+            syntax: entry.syntax.into_iter().map(Diagnostic::from).collect(),
+            messages,
+          });
+        }
+
+        let (parse_errors, original_messages) = self.process_file(&linter, &named_source);
+        let file = LintedFile {
+          syntax: to_syntax_diagnostics(parse_errors),
+          messages: Self::to_messages(named_source, original_messages),
+        };
+
+        if let Some(cache) = &cache {
+          let entry = CacheEntry {
+            syntax: file.syntax.iter().map(CachedDiagnostic::from).collect(),
+            lint: file.messages.iter().map(CachedDiagnostic::from).collect(),
+          };
+          // 写不进缓存只是下次慢一点，不影响结果
+          let _ = cache.put(&key, &file.messages.source_code, &entry);
         }
-        Ok(doctor_messages)
+
+        Ok(file)
       })
       .map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
 
+    // 只有遍历了整个项目，没用到的缓存才确定是过期的
+    if let Some(cache) = cache.filter(|_| self.files.is_none()) {
+      let _ = cache.prune();
+    }
+
     let res = res.into_iter().filter_map(|r| r.ok()).collect::<Vec<_>>();
 
     Ok(res)
   }
 
-  fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
-    let linter = self
-      .create_linter(FixKind::All)
-      .map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
+  fn collect(&self, fix_kind: FixKind) -> Result<Vec<Messages>, ValidatorError> {
+    let files = self.process_all(fix_kind)?;
+    Ok(files.into_iter().map(|file| file.messages).collect())
+  }
+}
 
-    let parallel = WalkParallelJs::builder()
-      .cwd(self.cwd.clone())
-      .ignore(self.ignore.clone())
-      .build();
+impl Validator for LintValidator {
+  fn meta(&self) -> ValidatorMeta {
+    // 规则来自 oxlintrc，所以 codes 是当前配置下开启的规则
+    let codes = serde_json::to_value(&self.oxlintrc)
+      .map(|config| enabled_rule_codes(&config))
+      .unwrap_or_default()
+      .into_iter()
+      .filter_map(|code| code.parse::<DiagnosticCode>().ok())
+      .collect();
+
+    ValidatorMeta::builder()
+      .id(VALIDATOR)
+      .description("Lints JavaScript and TypeScript sources with the configured oxlint rules")
+      .codes(codes)
+      .fixable(true)
+      .build()
+  }
 
-    let res = parallel
-      .walk(|path| -> Result<Messages, WalkError> {
-        let (named_source, original_messages) = self.process_file(&linter, &path)?;
-
-        let mut doctor_messages = Messages::builder()
-          .diagnostics(vec![])
-          .source_code(named_source.source_code.clone())
-          .source_path(named_source.file_path.clone())
-          .build();
-        if !original_messages.is_empty() {
-          let fixed_code = self.apply_fixes(&named_source.source_code, &original_messages);
-          fs::write(path, fixed_code.unwrap_or(named_source.source_code.clone()))
-            .map_err(|e| WalkError::Unknown(e.to_string()))?;
-        }
-        for msg in original_messages {
-          let diagnostic = doctor_core::Diagnostic::from(msg.error);
-          doctor_messages.push(diagnostic.into());
-        }
-        Ok(doctor_messages)
-      })
-      .map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
+  fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+    self.collect(FixKind::None)
+  }
 
-    let res = res.into_iter().filter_map(|r| r.ok()).collect::<Vec<_>>();
+  fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
+    let res = self.collect(FixKind::All)?;
+
+    for messages in &res {
+      messages.write_fixes()?;
+    }
 
     Ok(res)
   }
+
+  fn fixes(&self) -> Result<Vec<Messages>, ValidatorError> {
+    self.collect(FixKind::All)
+  }
+
+  fn fix_dry_run(&self) -> Result<Patch, ValidatorError> {
+    let res = self.fixes()?;
+    Ok(Patch::from_messages(&res))
+  }
 }
 
 impl LintValidator {
@@ -210,11 +300,13 @@ impl LintValidator {
     let parallel = WalkParallelJs::builder()
       .cwd(self.cwd.clone())
       .ignore(self.ignore.clone())
+      .files(self.files.clone())
       .build();
 
     let res = parallel
       .walk(|path| -> Result<FileDiagnostic, WalkError> {
-        let (named_source, original_messages) = self.process_file(&linter, &path)?;
+        let named_source = named_source::PathWithSource::try_from(&path)?;
+        let (_, original_messages) = self.process_file(&linter, &named_source);
         let diag = FileDiagnostic {
           file_path: named_source.file_path.clone(),
           diagnostics: original_messages.into_iter().map(|msg| msg.error).collect(),
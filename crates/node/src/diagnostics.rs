@@ -1,16 +1,34 @@
-use std::path::Path;
+use std::{ops::Range, path::Path};
 
-use miette::{LabeledSpan, MietteDiagnostic, SourceSpan, diagnostic};
+use doctor_core::{Diagnostic, DiagnosticCode, Fix, TextEdit};
+use miette::{LabeledSpan, SourceSpan, diagnostic};
+
+pub const VALIDATOR: &str = "node-version";
+
+/// Rules of every code [`DiagnosticFactory`] can produce
+const RULES: [&str; 4] = [
+  "config-file-not-found",
+  "invalid-version-range",
+  "invalid-version-format",
+  "empty-version",
+];
 
 pub struct DiagnosticFactory;
 
 impl DiagnosticFactory {
-  pub fn at_config_file_not_found<P: AsRef<Path>>(path: P, regex_str: &str) -> MietteDiagnostic {
+  pub fn codes() -> Vec<DiagnosticCode> {
+    RULES
+      .iter()
+      .map(|rule| DiagnosticCode::shined(VALIDATOR, *rule))
+      .collect()
+  }
+
+  pub fn at_config_file_not_found<P: AsRef<Path>>(path: P, regex_str: &str) -> Diagnostic {
     let dir = path.as_ref().parent().map_or(Path::new(""), |p| p);
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = "shined(node-version:config-file-not-found)",
+      code = DiagnosticCode::shined(VALIDATOR, "config-file-not-found").to_string(),
       help = format!(
         r#"Please add .node-version file to your project {}. 
 
@@ -21,14 +39,11 @@ e.g. 'v18.0.0' or '18.0.0'."#,
         regex_str,
       ),
       "Config file was not found.",
-    )
+    ))
   }
 
-  pub fn at_invalid_version_range(
-    span: impl Into<SourceSpan>,
-    ranges: Vec<String>,
-  ) -> MietteDiagnostic {
-    let code = "shined(node-version:invalid-version-range)";
+  pub fn at_invalid_version_range(span: impl Into<SourceSpan>, ranges: Vec<String>) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "invalid-version-range");
     let help = format!(
       r#"Select valid node version.
 
@@ -40,47 +55,54 @@ Range: {:?}"#,
       format!(r#"Only support version range in {:?}"#, ranges),
     )];
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "Invalid node version"
-    )
+    ))
   }
 
   pub fn at_invalid_version_format(
-    span: impl Into<SourceSpan>,
+    span: Range<usize>,
     regex_str: &str,
-  ) -> MietteDiagnostic {
-    let code = "shined(node-version:invalid-version-format)";
+    suggestion: Option<String>,
+  ) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "invalid-version-format");
     let help = format!(
       r#"Please modify your version number to meet the format '{}'."#,
       regex_str
     );
 
+    let fix = suggestion.map(|version| {
+      Fix::from(TextEdit::replace(span.clone(), version.as_str()))
+        .with_message(format!("Replace with {version}"))
+    });
+
     let labels = vec![LabeledSpan::at(span, "Invalid node version number format")];
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "Invalid node version number format",
-    )
+    ))
+    .with_fix(fix)
   }
 
-  pub fn at_empty_node_version(span: impl Into<SourceSpan>) -> MietteDiagnostic {
-    let code = "shined(node-version:empty-version)";
+  pub fn at_empty_node_version(span: impl Into<SourceSpan>) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "empty-version");
     let help = r#"Please add a node version to your .node-version file."#;
     let labels = vec![LabeledSpan::at(span, "Empty node version")];
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "Empty node version",
-    )
+    ))
   }
 }
@@ -1,14 +1,16 @@
 use std::{borrow::Cow, path::Path};
 
 use doctor_core::{
-  Messages, ValidatorError,
+  Diagnostic, Messages, ValidatorError, ValidatorMeta,
   traits::{PathExt, Validator},
 };
 use lazy_regex::regex;
-use miette::MietteDiagnostic;
 use typed_builder::TypedBuilder;
 
-use crate::{diagnostics::DiagnosticFactory, node_version::NodeVersion};
+use crate::{
+  diagnostics::{DiagnosticFactory, VALIDATOR},
+  node_version::NodeVersion,
+};
 
 /// validate node version file
 ///
@@ -36,7 +38,7 @@ where
 {
   config_path: P,
 
-  #[builder(default = None, setter(strip_option))]
+  #[builder(default = None, setter(strip_option(fallback = with_valid_range_opt)))]
   with_valid_range: Option<Vec<T>>,
 }
 
@@ -48,7 +50,7 @@ where
   fn validate_valid_range(
     &self,
     node_version: &NodeVersion,
-  ) -> Result<Vec<MietteDiagnostic>, ValidatorError> {
+  ) -> Result<Vec<Diagnostic>, ValidatorError> {
     let mut diagnostics = vec![];
 
     if let Some(with_valid_range) = &self.with_valid_range {
@@ -89,6 +91,15 @@ where
   P: AsRef<Path>,
   T: Into<Cow<'static, str>> + AsRef<str>,
 {
+  fn meta(&self) -> ValidatorMeta {
+    ValidatorMeta::builder()
+      .id(VALIDATOR)
+      .description("Checks that .node-version holds a supported Node.js version")
+      .codes(DiagnosticFactory::codes())
+      .fixable(true)
+      .build()
+  }
+
   /// validate node version file
   ///
   /// # Example
@@ -115,6 +126,8 @@ where
     if !path.exists() {
       return Ok(vec![
         Messages::builder()
+          .validator(VALIDATOR)
+          .source_path(path.to_string_owned())
           .diagnostics(vec![DiagnosticFactory::at_config_file_not_found(
             path,
             r.as_str(),
@@ -126,6 +139,7 @@ where
     let node_version = NodeVersion::parse(path)?;
 
     let mut messages = Messages::builder()
+      .validator(VALIDATOR)
       .source_code(node_version.__raw_source.clone().unwrap_or_default())
       .source_path(path.to_string_owned())
       .diagnostics(vec![])
@@ -133,7 +147,18 @@ where
 
     if let Some(version) = &node_version.version {
       if !r.is_match(&version) {
-        let diagnostic = DiagnosticFactory::at_invalid_version_format(0..version.len(), r.as_str());
+        let start = messages.source_code.find(version.as_str()).unwrap_or(0);
+
+        // e.g. `=18.0.0` or `18.0.0-rc.1` can be normalized to `18.0.0`
+        let suggestion = node_semver::Version::parse(version)
+          .ok()
+          .map(|v| format!("{}.{}.{}", v.major, v.minor, v.patch));
+
+        let diagnostic = DiagnosticFactory::at_invalid_version_format(
+          start..start + version.len(),
+          r.as_str(),
+          suggestion,
+        );
 
         messages.push(diagnostic);
 
@@ -160,7 +185,13 @@ where
   }
 
   fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
-    todo!()
+    let messages = self.validate()?;
+
+    for message in &messages {
+      message.write_fixes()?;
+    }
+
+    Ok(messages)
   }
 }
 
@@ -1,58 +1,113 @@
-use std::path::Path;
+use std::{ops::Range, path::Path};
 
-use miette::{LabeledSpan, MietteDiagnostic, SourceSpan, diagnostic};
+use doctor_core::{Diagnostic, DiagnosticCode, Fix, TextEdit};
+use miette::{LabeledSpan, diagnostic};
+
+pub const VALIDATOR: &str = "npmrc";
+
+/// Rules of every code [`DiagnosticFactory`] can produce
+const RULES: [&str; 3] = [
+  "config-file-not-found",
+  "invalid-registry",
+  "missing-registry",
+];
 
 pub struct DiagnosticFactory;
 
 impl DiagnosticFactory {
-  pub fn at_config_file_not_found<P: AsRef<Path>>(path: P) -> MietteDiagnostic {
+  pub fn codes() -> Vec<DiagnosticCode> {
+    RULES
+      .iter()
+      .map(|rule| DiagnosticCode::shined(VALIDATOR, *rule))
+      .collect()
+  }
+
+  pub fn at_config_file_not_found<P: AsRef<Path>>(
+    path: P,
+    validate_registry: Option<&[String]>,
+  ) -> Diagnostic {
     let file = path.as_ref();
     let dir = file.parent().map_or(Path::new(""), |p| p);
 
-    let code = "shined(npmrc:config-file-not-found)";
+    let code = DiagnosticCode::shined(VALIDATOR, "config-file-not-found");
     let help = format!(
       "Please add a .npmrc file to your project at {}",
       dir.display().to_string()
     );
 
-    diagnostic!(
+    let fix = validate_registry
+      .and_then(|registry| registry.first())
+      .map(|registry| {
+        Fix::from(TextEdit::insert(0, format!("registry={registry}\n")))
+          .with_message("Create .npmrc with registry")
+      });
+
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       "Config file was not found: {}",
       file.display().to_string()
-    )
+    ))
+    .with_fix(fix)
   }
 
+  /// `span` is the registry value, without it the diagnostic has no label and no fix
   pub fn at_invalid_registry(
-    span: impl Into<SourceSpan>,
+    span: Option<Range<usize>>,
     validate_registry: &[String],
-  ) -> MietteDiagnostic {
-    let code = "shined(npmrc:invalid-registry)";
+  ) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "invalid-registry");
     let help = format!("Only support registry: {:?}", validate_registry);
 
-    let labels = vec![LabeledSpan::underline(span)];
+    let fix = span
+      .clone()
+      .zip(validate_registry.first())
+      .map(|(span, registry)| {
+        Fix::from(TextEdit::replace(span, registry.as_str()))
+          .with_message(format!("Replace registry with {registry}"))
+      });
+
+    let labels = span
+      .map(LabeledSpan::underline)
+      .into_iter()
+      .collect::<Vec<_>>();
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       r#"Invalid registry "#,
-    )
+    ))
+    .with_fix(fix)
   }
 
-  pub fn at_missing_registry(span: impl Into<SourceSpan>) -> MietteDiagnostic {
-    let code = "shined(npmrc:missing-registry)";
+  pub fn at_missing_registry(source: &str, validate_registry: &[String]) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "missing-registry");
     let help = "Please add a registry field to your .npmrc file";
-    let labels = vec![LabeledSpan::underline(span)];
+    let labels = vec![LabeledSpan::underline(0..source.len())];
+
+    let fix = validate_registry.first().map(|registry| {
+      let separator = if source.is_empty() || source.ends_with('\n') {
+        ""
+      } else {
+        "\n"
+      };
+      Fix::from(TextEdit::insert(
+        source.len(),
+        format!("{separator}registry={registry}\n"),
+      ))
+      .with_message(format!("Add registry={registry}"))
+    });
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "Missing registry field",
-    )
+    ))
+    .with_fix(fix)
   }
 }
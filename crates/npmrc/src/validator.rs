@@ -1,14 +1,15 @@
-use std::{borrow::Cow, path::Path};
+use std::{borrow::Cow, ops::Range, path::Path};
 
 use doctor_core::{
-  Messages, ValidatorError,
+  Diagnostic, Messages, ValidatorError, ValidatorMeta,
   traits::{PathExt, Validator},
 };
-
-use miette::MietteDiagnostic;
 use typed_builder::TypedBuilder;
 
-use crate::{diagnostics::DiagnosticFactory, npmrc_config::NpmrcConfig};
+use crate::{
+  diagnostics::{DiagnosticFactory, VALIDATOR},
+  npmrc_config::NpmrcConfig,
+};
 
 /// NpmrcValidator is a validator for npmrc file
 ///
@@ -32,7 +33,7 @@ where
 {
   config_path: P,
 
-  #[builder(default = None,setter(strip_option))]
+  #[builder(default = None, setter(strip_option(fallback = with_registry_url_opt)))]
   with_registry_url: Option<Vec<S>>,
 }
 
@@ -41,10 +42,7 @@ where
   P: AsRef<Path>,
   S: Into<Cow<'static, str>> + AsRef<str>,
 {
-  fn validate_registry(
-    &self,
-    config: &NpmrcConfig,
-  ) -> Result<Vec<MietteDiagnostic>, ValidatorError> {
+  fn validate_registry(&self, config: &NpmrcConfig) -> Result<Vec<Diagnostic>, ValidatorError> {
     let mut diagnostics = vec![];
 
     if let Some(validate_registry) = &self.with_registry_url {
@@ -58,19 +56,17 @@ where
           .iter()
           .any(|item| item.trim_end_matches("/") == registry.trim_end_matches("/"))
         {
-          let (offset, length) = self
-            .find_registry_position(&config.__raw_source)
-            .unwrap_or((0, 0));
+          let span = find_registry_span(&config.__raw_source);
 
-          let diagnostic =
-            DiagnosticFactory::at_invalid_registry(offset..offset + length, &validate_registry);
+          let diagnostic = DiagnosticFactory::at_invalid_registry(span, &validate_registry);
 
           diagnostics.push(diagnostic);
 
           return Ok(diagnostics);
         }
       } else {
-        let diagnostic = DiagnosticFactory::at_missing_registry(0..config.__raw_source.len());
+        let diagnostic =
+          DiagnosticFactory::at_missing_registry(&config.__raw_source, &validate_registry);
 
         diagnostics.push(diagnostic);
 
@@ -80,21 +76,46 @@ where
     Ok(diagnostics)
   }
 
-  fn find_registry_position(&self, content: &str) -> Option<(usize, usize)> {
-    for line in content.lines() {
-      if let Some(key_pos) = line.find("registry=") {
-        let equals_pos = key_pos + "registry".len();
-        let value_start = equals_pos + 1;
-        let value_length = line.len() - value_start;
-
-        let line_offset = content.find(line).unwrap();
-        let absolute_offset = line_offset + value_start;
+  fn registry_urls(&self) -> Option<Vec<String>> {
+    self.with_registry_url.as_ref().map(|registry| {
+      registry
+        .iter()
+        .map(|item| item.as_ref().to_string())
+        .collect()
+    })
+  }
+}
 
-        return Some((absolute_offset, value_length));
-      }
+/// Byte range of the value of the unscoped `registry` key
+///
+/// `None` when there is no such line or more than one, so a fix never rewrites the wrong value.
+/// Scoped keys like `@scope:registry` and comments are skipped.
+fn find_registry_span(content: &str) -> Option<Range<usize>> {
+  let mut span = None;
+  let mut found = 0;
+  let mut offset = 0;
+
+  for line in content.split_inclusive('\n') {
+    let start = offset;
+    offset += line.len();
+
+    let line = line.trim_end_matches(['\n', '\r']);
+    if line.trim_start().starts_with(['#', ';']) {
+      continue;
+    }
+    let Some((key, value)) = line.split_once('=') else {
+      continue;
+    };
+    if key.trim() != "registry" {
+      continue;
     }
-    None
+
+    let value_start = start + key.len() + 1 + (value.len() - value.trim_start().len());
+    span = Some(value_start..value_start + value.trim().len());
+    found += 1;
   }
+
+  if found == 1 { span } else { None }
 }
 
 impl<P, S> Validator for NpmrcValidator<P, S>
@@ -102,6 +123,15 @@ where
   P: AsRef<Path>,
   S: Into<Cow<'static, str>> + AsRef<str>,
 {
+  fn meta(&self) -> ValidatorMeta {
+    ValidatorMeta::builder()
+      .id(VALIDATOR)
+      .description("Checks that .npmrc exists and points at an allowed registry")
+      .codes(DiagnosticFactory::codes())
+      .fixable(true)
+      .build()
+  }
+
   /// Validate npmrc file
   ///
   /// # Example
@@ -122,15 +152,20 @@ where
     if !path.exists() {
       return Ok(vec![
         Messages::builder()
+          .validator(VALIDATOR)
           .source_code(String::new())
           .source_path(path.to_string_owned())
-          .diagnostics(vec![DiagnosticFactory::at_config_file_not_found(path)])
+          .diagnostics(vec![DiagnosticFactory::at_config_file_not_found(
+            path,
+            self.registry_urls().as_deref(),
+          )])
           .build(),
       ]);
     }
     let config = NpmrcConfig::parse(self.config_path.as_ref())?;
 
     let mut messages = Messages::builder()
+      .validator(VALIDATOR)
       .source_code(config.__raw_source.clone())
       .source_path(self.config_path.as_ref().to_string_owned())
       .diagnostics(vec![])
@@ -144,7 +179,13 @@ where
   }
 
   fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
-    todo!()
+    let messages = self.validate()?;
+
+    for message in &messages {
+      message.write_fixes()?;
+    }
+
+    Ok(messages)
   }
 }
 
@@ -243,6 +284,58 @@ mod tests {
     }
   }
 
+  #[test]
+  fn should_attach_fix_to_invalid_registry_diagnostic() {
+    let result = NpmrcValidator::builder()
+      .config_path("fixtures/.npmrc")
+      .with_registry_url(vec!["https://test2.npmjs.org"])
+      .build()
+      .validate()
+      .unwrap();
+
+    for msg in result {
+      assert!(msg.has_fix());
+      let fixed = msg.apply_fixes();
+      assert!(fixed.is_changed());
+      assert!(fixed.output.contains("registry=https://test2.npmjs.org"));
+    }
+  }
+
+  #[test]
+  fn should_fix_registry_with_spaces_around_equals() {
+    let result = NpmrcValidator::builder()
+      .config_path("fixtures/.spaced_registry")
+      .with_registry_url(vec!["https://test2.npmjs.org"])
+      .build()
+      .validate()
+      .unwrap();
+
+    let fixed = result[0].apply_fixes();
+    assert_eq!(fixed.output, "registry = https://test2.npmjs.org\n");
+  }
+
+  #[test]
+  fn should_fix_unscoped_registry_only() {
+    let result = NpmrcValidator::builder()
+      .config_path("fixtures/.scoped_registry")
+      .with_registry_url(vec!["https://test2.npmjs.org"])
+      .build()
+      .validate()
+      .unwrap();
+
+    let fixed = result[0].apply_fixes();
+    assert_eq!(
+      fixed.output,
+      "@scope:registry=https://scope.npmjs.org/\nregistry=https://test2.npmjs.org\n"
+    );
+  }
+
+  #[test]
+  fn should_not_fix_registry_that_can_not_be_located() {
+    assert_eq!(find_registry_span("registry=a\nregistry=b\n"), None);
+    assert_eq!(find_registry_span("; registry=a\n"), None);
+  }
+
   #[test]
   fn should_return_config_file_not_found_diagnostic() {
     let result = NpmrcValidator::builder()
@@ -1,127 +1,178 @@
-use std::path::Path;
+use std::{ops::Range, path::Path};
 
+use doctor_core::{Diagnostic, DiagnosticCode, Fix, TextEdit};
 use miette::{LabeledSpan, MietteDiagnostic, SourceSpan, diagnostic};
 
+pub const VALIDATOR: &str = "package-json";
+
+/// Insert `"key": value` as the first property of the root object
+fn insert_property(source: &str, key: &str, value: &str) -> Option<TextEdit> {
+  let open = source.find('{')?;
+  let rest = &source[open + 1..];
+
+  let is_empty_object = rest.trim_start().starts_with('}');
+
+  let indent = rest
+    .lines()
+    .skip(1)
+    .find(|line| !line.trim().is_empty())
+    .map(|line| &line[..line.len() - line.trim_start().len()])
+    .filter(|indent| !indent.is_empty())
+    .unwrap_or("  ");
+
+  let content = if is_empty_object {
+    format!("\n{indent}\"{key}\": {value}\n")
+  } else {
+    format!("\n{indent}\"{key}\": {value},")
+  };
+
+  Some(TextEdit::insert(open + 1, content))
+}
+
+/// Rules of every code [`DiagnosticFactory`] can produce
+const RULES: [&str; 7] = [
+  "config-file-not-found",
+  "library-version-not-allowed",
+  "missing-package-manager",
+  "missing-private",
+  "missing-name",
+  "private-not-true",
+  "private-type-error",
+];
+
 pub struct DiagnosticFactory;
 
 impl DiagnosticFactory {
-  pub fn at_config_file_not_found<P: AsRef<Path>>(path: P) -> MietteDiagnostic {
+  pub fn codes() -> Vec<DiagnosticCode> {
+    RULES
+      .iter()
+      .map(|rule| DiagnosticCode::shined(VALIDATOR, *rule))
+      .collect()
+  }
+
+  pub fn at_config_file_not_found<P: AsRef<Path>>(path: P) -> Diagnostic {
     let file = path.as_ref();
     let dir = file.parent().map_or(Path::new(""), |p| p);
-    let code = "shined(package-json:config-file-not-found)";
+    let code = DiagnosticCode::shined(VALIDATOR, "config-file-not-found");
     let help = format!(
       "Please add a package.json file to your project at {}",
       dir.display().to_string()
     );
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       "Config file was not found: {}",
       file.display().to_string()
-    )
+    ))
   }
 
-  pub fn at_wrong_shineout_version(
-    span: impl Into<SourceSpan>,
-    migration: &str,
-  ) -> MietteDiagnostic {
-    let code = "shined(package-json:library-version-not-allowed)";
+  pub fn at_wrong_shineout_version(span: Range<usize>, migration: &str) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "library-version-not-allowed");
     let help = "Please remove the [*, http, https] version from the package.json file";
+    let fix = Fix::from(TextEdit::replace(span.clone(), format!(r#""{migration}""#)))
+      .with_message(format!("Migrate to {migration}"));
     let labels = vec![LabeledSpan::at(
       span,
       format!(r##"Please migration to "{migration}", Critical faults in the current version. "##),
     )];
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "The library version is not allowed",
-    )
+    ))
+    .with_fix(fix)
   }
 
-  pub fn at_library_version_not_allowed(span: impl Into<SourceSpan>) -> MietteDiagnostic {
-    let code = "shined(package-json:library-version-not-allowed)";
+  pub fn at_library_version_not_allowed(span: impl Into<SourceSpan>) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "library-version-not-allowed");
     let help = "Please remove the [*, http, https] version from the package.json file";
     let labels = vec![LabeledSpan::at(
       span,
       "The version is not allowed to use [*, http, https]",
     )];
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "The library version is not allowed",
-    )
+    ))
   }
 
-  pub fn at_missing_package_manager(span: impl Into<SourceSpan>) -> MietteDiagnostic {
-    let code = "shined(package-json:missing-package-manager)";
+  pub fn at_missing_package_manager(span: impl Into<SourceSpan>) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "missing-package-manager");
     let help = r#"Add packageManager field to your package.json file. 
     
 e.g.:  "packageManager": "npm@8.19.2""#;
     let labels = vec![LabeledSpan::at(span, "Add packageManager field here")];
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "Missing 'packageManager' field",
-    )
+    ))
   }
 
-  pub fn at_missing_private_field(span: impl Into<SourceSpan>) -> MietteDiagnostic {
-    let code = "shined(package-json:missing-private)";
+  pub fn at_missing_private_field(source: &str) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "missing-private");
     let help = r#"Add private field to your package.json file.
     
 e.g.: "private": true"#;
-    let labels = vec![LabeledSpan::at(span, "Add private field here")];
+    let fix = insert_property(source, "private", "true")
+      .map(|edit| Fix::from(edit).with_message(r#"Add "private": true"#));
+    let labels = vec![LabeledSpan::at(0..source.len(), "Add private field here")];
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "Missing 'private' field",
-    )
+    ))
+    .with_fix(fix)
   }
 
-  pub fn at_missing_name_field(span: impl Into<SourceSpan>) -> MietteDiagnostic {
-    let code = "shined(package-json:missing-name)";
+  pub fn at_missing_name_field(span: impl Into<SourceSpan>) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "missing-name");
     let help = r#"Add name field to your package.json file.
     
 e.g.: "name": "my-package""#;
     let labels = vec![LabeledSpan::at(span, "Add name field here")];
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "Missing 'name' field",
-    )
+    ))
   }
 
-  pub fn at_private_not_true(span: impl Into<SourceSpan>) -> MietteDiagnostic {
-    let code = "shined(package-json:private-not-true)";
+  pub fn at_private_not_true(span: Range<usize>) -> Diagnostic {
+    let code = DiagnosticCode::shined(VALIDATOR, "private-not-true");
     let help = r#"Update your package.json to include: "private": true
 
 This ensures the package cannot be accidentally published to npm."#;
+    let fix =
+      Fix::from(TextEdit::replace(span.clone(), "true")).with_message(r#"Set "private": true"#);
     let labels = vec![LabeledSpan::at(span, "Set private field to true")];
 
-    diagnostic!(
+    Diagnostic::from(diagnostic!(
       severity = miette::Severity::Error,
-      code = code,
+      code = code.to_string(),
       help = help,
       labels = labels,
       "The 'private' field in package.json must be set to true",
-    )
+    ))
+    .with_fix(fix)
   }
 
-  pub fn at_private_type_error(labels: Vec<LabeledSpan>) -> MietteDiagnostic {
+  pub fn at_private_type_error(labels: Vec<LabeledSpan>) -> Diagnostic {
     let message = "The 'private' field in package.json must be a boolean";
     let b = MietteDiagnostic::new(message)
       .with_labels(labels)
@@ -129,8 +180,34 @@ This ensures the package cannot be accidentally published to npm."#;
         r#"Update your package.json to include: "private": true
 This ensures the package cannot be accidentally published to npm."#,
       )
-      .with_code("shined(package-json:private-type-error)")
+      .with_code(DiagnosticCode::shined(VALIDATOR, "private-type-error"))
       .with_severity(miette::Severity::Error);
-    b
+    Diagnostic::from(b)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use doctor_core::apply_fixes;
+
+  use super::*;
+
+  #[test]
+  fn should_insert_property_into_object() {
+    let source = "{\n    \"name\": \"demo\"\n}\n";
+    let edit = insert_property(source, "private", "true").unwrap();
+    let result = apply_fixes(source, &[Fix::from(edit)]);
+    assert_eq!(
+      result.output,
+      "{\n    \"private\": true,\n    \"name\": \"demo\"\n}\n"
+    );
+  }
+
+  #[test]
+  fn should_insert_property_into_empty_object() {
+    let source = "{}";
+    let edit = insert_property(source, "private", "true").unwrap();
+    let result = apply_fixes(source, &[Fix::from(edit)]);
+    assert_eq!(result.output, "{\n  \"private\": true\n}");
   }
 }
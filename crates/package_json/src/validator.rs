@@ -1,10 +1,9 @@
-use crate::diagnostics::DiagnosticFactory;
+use crate::diagnostics::{DiagnosticFactory, VALIDATOR};
 use doctor_core::{
-  Messages, ValidatorError,
+  Diagnostic, Messages, ValidatorError, ValidatorMeta,
   traits::{PathExt, Validator},
 };
 use jsonc_parser::{CollectOptions, ParseOptions, common::Ranged, parse_to_ast};
-use miette::MietteDiagnostic;
 use node_semver::Range;
 use package_json_parser::{FxHashMap, PackageJsonParser};
 use std::{fs::read_to_string, path::Path};
@@ -125,16 +124,16 @@ where
 {
   config_path: P,
 
-  #[builder(default = None, setter(strip_option))]
+  #[builder(default = None, setter(strip_option(fallback = with_validate_name_opt)))]
   with_validate_name: Option<ValidateName>,
 
-  #[builder(default = None, setter(strip_option))]
+  #[builder(default = None, setter(strip_option(fallback = with_validate_private_opt)))]
   with_validate_private: Option<ValidatePrivate>,
 
-  #[builder(default = None, setter(strip_option))]
+  #[builder(default = None, setter(strip_option(fallback = with_validate_package_manager_opt)))]
   with_validate_package_manager: Option<ValidatePackageManager>,
 
-  #[builder(default = None, setter(strip_option))]
+  #[builder(default = None, setter(strip_option(fallback = with_validate_shineout_version_opt)))]
   with_validate_shineout_version: Option<bool>,
 }
 
@@ -164,7 +163,7 @@ where
   fn validate_package_manager(
     &self,
     package_json: &PackageJsonParser,
-  ) -> Result<Vec<MietteDiagnostic>, ValidatorError> {
+  ) -> Result<Vec<Diagnostic>, ValidatorError> {
     let mut diagnostics = vec![];
 
     if let Some(_) = &self.with_validate_package_manager {
@@ -186,7 +185,7 @@ where
   fn validate_library_version(
     &self,
     package_json: &PackageJsonParser,
-  ) -> Result<Vec<MietteDiagnostic>, ValidatorError> {
+  ) -> Result<Vec<Diagnostic>, ValidatorError> {
     let mut diagnostics = vec![];
 
     let parse_result = parse_to_ast(
@@ -271,7 +270,7 @@ where
   fn validate_private(
     &self,
     package_json: &PackageJsonParser,
-  ) -> Result<Vec<MietteDiagnostic>, ValidatorError> {
+  ) -> Result<Vec<Diagnostic>, ValidatorError> {
     let mut diagnostics = vec![];
 
     if let Some(validate_private) = &self.with_validate_private {
@@ -295,11 +294,11 @@ where
           }
         };
       } else {
-        let len = package_json
+        let source = package_json
           .__raw_source
           .as_ref()
-          .map_or(0, |source| source.len());
-        diagnostics.push(DiagnosticFactory::at_missing_private_field(0..len));
+          .map_or("", |source| source);
+        diagnostics.push(DiagnosticFactory::at_missing_private_field(source));
         return Ok(diagnostics);
       }
     }
@@ -310,7 +309,7 @@ where
   fn validate_name(
     &self,
     package_json: &PackageJsonParser,
-  ) -> Result<Vec<MietteDiagnostic>, ValidatorError> {
+  ) -> Result<Vec<Diagnostic>, ValidatorError> {
     let mut diagnostics = vec![];
 
     if let Some(_) = &self.with_validate_name {
@@ -331,6 +330,17 @@ impl<P> Validator for PackageJsonValidator<P>
 where
   P: AsRef<Path>,
 {
+  fn meta(&self) -> ValidatorMeta {
+    ValidatorMeta::builder()
+      .id(VALIDATOR)
+      .description(
+        "Checks the required fields of package.json and the versions of pinned libraries",
+      )
+      .codes(DiagnosticFactory::codes())
+      .fixable(true)
+      .build()
+  }
+
   /// validate package.json file
   ///
   /// # Example
@@ -354,6 +364,7 @@ where
     if !path.exists() {
       return Ok(vec![
         Messages::builder()
+          .validator(VALIDATOR)
           .source_code(String::new())
           .source_path(path.to_string_owned())
           .diagnostics(vec![DiagnosticFactory::at_config_file_not_found(path)])
@@ -364,6 +375,7 @@ where
     let raw = read_to_string(path)?;
 
     let mut messages = Messages::builder()
+      .validator(VALIDATOR)
       .source_code(raw)
       .source_path(path.to_string_owned())
       .diagnostics(vec![])
@@ -413,7 +425,13 @@ where
   }
 
   fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
-    todo!()
+    let messages = self.validate()?;
+
+    for message in &messages {
+      message.write_fixes()?;
+    }
+
+    Ok(messages)
   }
 }
 
@@ -473,6 +491,39 @@ mod tests {
     }
   }
 
+  #[test]
+  fn should_fix_missing_private_diagnostic() {
+    let result = PackageJsonValidator::builder()
+      .config_path("fixtures/no_private.json")
+      .with_validate_private(ValidatePrivate::True)
+      .build()
+      .validate()
+      .unwrap();
+
+    for msg in result {
+      let fixed = msg.apply_fixes();
+      assert!(fixed.is_changed());
+      let value: serde_json::Value = serde_json::from_str(&fixed.output).unwrap();
+      assert_eq!(value["private"], serde_json::Value::Bool(true));
+    }
+  }
+
+  #[test]
+  fn should_fix_private_not_true_diagnostic() {
+    let result = PackageJsonValidator::builder()
+      .config_path("fixtures/private_false.json")
+      .with_validate_private(ValidatePrivate::True)
+      .build()
+      .validate()
+      .unwrap();
+
+    for msg in result {
+      let fixed = msg.apply_fixes();
+      let value: serde_json::Value = serde_json::from_str(&fixed.output).unwrap();
+      assert_eq!(value["private"], serde_json::Value::Bool(true));
+    }
+  }
+
   #[test]
   fn should_return_missing_package_manager_diagnostic() {
     let result = PackageJsonValidator::builder()
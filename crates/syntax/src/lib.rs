@@ -1,29 +1,89 @@
-use std::{fs::read_to_string, path::PathBuf};
+use std::{fmt::Debug, fs::read_to_string, path::PathBuf, sync::Arc};
 
-use doctor_core::{Ignore, Messages, hack_source_type_from_path, traits::Validator};
+use doctor_core::{
+  ChangedFiles, Diagnostic, DiagnosticCode, Ignore, Messages, ValidatorError, ValidatorMeta,
+  hack_source_type_from_path,
+  traits::{DiagnosticCodeExt, Validator},
+};
 use doctor_walk::{WalkError, WalkParallelJs};
-use miette::MietteDiagnostic;
-use oxc::{allocator::Allocator, parser::Parser};
+use oxc::{allocator::Allocator, diagnostics::OxcDiagnostic, parser::Parser};
 use typed_builder::TypedBuilder;
 
+pub const VALIDATOR: &str = "syntax";
+
+/// Parse results produced elsewhere, e.g. by a pass shared with the linter
+///
+/// When a [`SyntaxValidator`] has a source it reports its messages instead of walking and
+/// parsing the project itself.
+pub trait SyntaxSource: Debug + Send + Sync {
+  fn syntax_messages(&self) -> Result<Vec<Messages>, ValidatorError>;
+}
+
+/// Convert oxc parser errors into syntax diagnostics
+///
+/// Errors without a code get `shined(syntax:parse-error)`, identical errors (same message and
+/// labels) are only kept once.
+pub fn to_syntax_diagnostics(errors: Vec<OxcDiagnostic>) -> Vec<Diagnostic> {
+  let mut diagnostics: Vec<Diagnostic> = Vec::with_capacity(errors.len());
+
+  for error in errors {
+    let mut diagnostic = Diagnostic::from(error);
+    // oxc parser errors carry no code
+    if diagnostic
+      .diagnostic_code()
+      .is_none_or(|code| code.is_unknown())
+    {
+      diagnostic.code = Some(DiagnosticCode::shined(VALIDATOR, "parse-error").into());
+    }
+
+    // 解析器在恢复时可能对同一位置重复报错
+    let duplicated = diagnostics
+      .iter()
+      .any(|d| d.message == diagnostic.message && d.labels == diagnostic.labels);
+    if !duplicated {
+      diagnostics.push(diagnostic);
+    }
+  }
+
+  diagnostics
+}
+
+/// The syntax [`Messages`] of one file, see [`to_syntax_diagnostics`]
+pub fn syntax_messages(
+  source_path: String,
+  source_code: String,
+  diagnostics: Vec<Diagnostic>,
+) -> Messages {
+  Messages::builder()
+    .validator(VALIDATOR)
+    .source_code(source_code)
+    .source_path(source_path)
+    .diagnostics(diagnostics)
+    .build()
+}
+
 #[derive(Debug, TypedBuilder)]
 pub struct SyntaxValidator {
   cwd: PathBuf,
   #[builder(default = Ignore::default())]
   pub ignore: Ignore,
+  #[builder(default = None, setter(strip_option))]
+  source: Option<Arc<dyn SyntaxSource>>,
+  /// Only parse these files instead of every file under `cwd`
+  #[builder(default = None)]
+  files: Option<ChangedFiles>,
 }
 
-impl Validator for SyntaxValidator {
-  fn validate(&self) -> Result<Vec<doctor_core::Messages>, doctor_core::ValidatorError> {
+impl SyntaxValidator {
+  fn parse_all(&self) -> Result<Vec<Messages>, ValidatorError> {
     let parallel = WalkParallelJs::builder()
       .cwd(self.cwd.clone())
       .ignore(self.ignore.clone())
+      .files(self.files.clone())
       .build();
 
     let res = parallel
       .walk(|path| -> Result<Messages, WalkError> {
-        let path = path.clone();
-
         let source_code = read_to_string(&path)?;
 
         let allocator = Allocator::default();
@@ -34,44 +94,47 @@ impl Validator for SyntaxValidator {
 
         let parse = parser.parse();
 
-        if !parse.errors.is_empty() {
-          let messages = parse
-            .errors
-            .into_iter()
-            .map(|item| doctor_core::Diagnostic::from(item).into())
-            .collect::<Vec<MietteDiagnostic>>();
-
-          return Ok(
-            Messages::builder()
-              .source_code(source_code.clone())
-              .source_path(path.display().to_string())
-              .diagnostics(messages)
-              .build(),
-          );
-        }
-
-        Ok(
-          Messages::builder()
-            .source_code(source_code.clone())
-            .source_path(path.display().to_string())
-            .diagnostics(vec![])
-            .build(),
-        )
+        Ok(syntax_messages(
+          path.display().to_string(),
+          source_code.clone(),
+          to_syntax_diagnostics(parse.errors),
+        ))
       })
-      .map_err(|e| doctor_core::ValidatorError::Unknown(Box::new(e)))?;
+      .map_err(|e| ValidatorError::Unknown(Box::new(e)))?;
 
     let res = res.into_iter().filter_map(|r| r.ok()).collect::<Vec<_>>();
 
     Ok(res)
   }
+}
 
-  fn fix(&self) -> Result<Vec<Messages>, doctor_core::ValidatorError> {
-    todo!()
+impl Validator for SyntaxValidator {
+  fn meta(&self) -> ValidatorMeta {
+    ValidatorMeta::builder()
+      .id(VALIDATOR)
+      .description("Reports JavaScript and TypeScript files that fail to parse")
+      .codes(vec![DiagnosticCode::shined(VALIDATOR, "parse-error")])
+      .fixable(false)
+      .build()
+  }
+
+  fn validate(&self) -> Result<Vec<Messages>, ValidatorError> {
+    match &self.source {
+      Some(source) => source.syntax_messages(),
+      None => self.parse_all(),
+    }
+  }
+
+  /// Parse errors have no automatic fix, fixing reports the same messages as validating
+  fn fix(&self) -> Result<Vec<Messages>, ValidatorError> {
+    self.validate()
   }
 }
 
 #[cfg(test)]
 mod tests {
+  use oxc::span::Span;
+
   use super::*;
 
   #[test]
@@ -84,4 +147,61 @@ mod tests {
       item.render();
     }
   }
+
+  #[test]
+  fn should_dedupe_identical_parse_errors() {
+    let error = || OxcDiagnostic::error("Unexpected token").with_label(Span::new(4, 5));
+
+    let diagnostics = to_syntax_diagnostics(vec![
+      error(),
+      error(),
+      OxcDiagnostic::error("Unexpected token").with_label(Span::new(8, 9)),
+    ]);
+
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(
+      diagnostics[0].code.as_deref(),
+      Some("shined(syntax:parse-error)")
+    );
+  }
+
+  #[derive(Debug)]
+  struct Shared;
+
+  impl SyntaxSource for Shared {
+    fn syntax_messages(&self) -> Result<Vec<Messages>, ValidatorError> {
+      Ok(vec![syntax_messages(
+        "a.js".to_string(),
+        "let = ;".to_string(),
+        to_syntax_diagnostics(vec![OxcDiagnostic::error("Unexpected token")]),
+      )])
+    }
+  }
+
+  #[test]
+  fn should_report_messages_of_shared_source() {
+    let validator = SyntaxValidator::builder()
+      .cwd(PathBuf::from("./not-walked"))
+      .source(Arc::new(Shared))
+      .build();
+
+    let res = validator.validate().unwrap();
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].source_path, "a.js");
+    assert_eq!(res[0].len(), 1);
+  }
+
+  #[test]
+  fn should_report_same_messages_when_fixing() {
+    let validator = SyntaxValidator::builder()
+      .cwd(PathBuf::from("./not-walked"))
+      .source(Arc::new(Shared))
+      .build();
+
+    let res = validator.fix().unwrap();
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].source_path, "a.js");
+  }
 }
@@ -10,6 +10,9 @@ pub enum WalkError {
   #[error("IO error: {0}")]
   IoError(#[from] std::io::Error),
 
+  #[error("Invalid ignore pattern: {0}")]
+  IgnoreError(#[from] ignore::Error),
+
   #[error("Unknown error: {0}")]
   Unknown(String),
 }
@@ -0,0 +1,140 @@
+use std::path::{Component, Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// The standard filters of [`ignore::WalkBuilder`] for paths that are not reached by walking:
+/// hidden files, `.ignore`, `.gitignore`, `.git/info/exclude` and the global gitignore
+///
+/// Like the walker, the git ignore files only apply inside a git repository and `.ignore`
+/// wins over all of them.
+#[derive(Debug, Clone)]
+pub(crate) struct StandardFilters {
+  cwd: PathBuf,
+  /// `cwd` made absolute, the parents are only found from there
+  root: PathBuf,
+  git_root: Option<PathBuf>,
+  /// `.ignore` and `.gitignore` of `cwd` and its parents in the repository, outermost first
+  parents: Vec<Matchers>,
+  /// `.git/info/exclude`, then the global gitignore
+  git: Vec<Gitignore>,
+}
+
+/// The ignore files of one directory
+#[derive(Debug, Clone)]
+struct Matchers {
+  ignore: Gitignore,
+  gitignore: Option<Gitignore>,
+}
+
+impl StandardFilters {
+  pub fn new(cwd: &Path) -> Self {
+    let root = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    let git_root = root
+      .ancestors()
+      .find(|dir| dir.join(".git").exists())
+      .map(Path::to_path_buf);
+
+    let parents = match &git_root {
+      Some(git_root) => root
+        .ancestors()
+        .take_while(|dir| dir.starts_with(git_root))
+        .collect::<Vec<_>>(),
+      None => vec![root.as_path()],
+    };
+    let parents = parents
+      .into_iter()
+      .rev()
+      .map(|dir| Matchers::new(dir, git_root.is_some()))
+      .collect();
+
+    let git = match &git_root {
+      Some(git_root) => {
+        let exclude = build(
+          git_root,
+          &git_root.join(".git").join("info").join("exclude"),
+        );
+        let (global, _) = GitignoreBuilder::new(git_root).build_global();
+        vec![exclude, global]
+      }
+      None => vec![],
+    };
+
+    Self {
+      cwd: cwd.to_path_buf(),
+      root,
+      git_root,
+      parents,
+      git,
+    }
+  }
+
+  /// Whether the walker would skip `path` or one of its directories below `cwd`
+  ///
+  /// `path` need not exist, only its directories are read for ignore files.
+  pub fn is_filtered(&self, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(&self.cwd) else {
+      return false;
+    };
+
+    let mut nested = Vec::new();
+    let mut current = self.root.clone();
+    let components = relative.components().collect::<Vec<_>>();
+
+    for (i, component) in components.iter().enumerate() {
+      let Component::Normal(name) = component else {
+        continue;
+      };
+      if name.to_string_lossy().starts_with('.') {
+        return true;
+      }
+
+      current.push(name);
+      let is_dir = i + 1 < components.len() || current.is_dir();
+      if self.is_ignored(&nested, &current, is_dir) {
+        return true;
+      }
+      if is_dir {
+        nested.push(Matchers::new(&current, self.git_root.is_some()));
+      }
+    }
+
+    false
+  }
+
+  /// `nested` are the ignore files of the directories below `cwd`, outermost first
+  fn is_ignored(&self, nested: &[Matchers], path: &Path, is_dir: bool) -> bool {
+    let levels = self.parents.iter().chain(nested).collect::<Vec<_>>();
+    // 与 ignore 的优先级一致：.ignore > .gitignore > .git/info/exclude > 全局，同类里越深越优先
+    let ignores = levels.iter().rev().map(|level| &level.ignore);
+    let gitignores = levels
+      .iter()
+      .rev()
+      .filter_map(|level| level.gitignore.as_ref());
+
+    ignores
+      .chain(gitignores)
+      .chain(&self.git)
+      .map(|matcher| matcher.matched(path, is_dir))
+      .find(|matched| !matched.is_none())
+      .is_some_and(|matched| matched.is_ignore())
+  }
+}
+
+impl Matchers {
+  fn new(dir: &Path, in_git_repo: bool) -> Self {
+    Self {
+      ignore: build(dir, &dir.join(".ignore")),
+      gitignore: in_git_repo.then(|| build(dir, &dir.join(".gitignore"))),
+    }
+  }
+}
+
+/// An empty matcher when `file` does not exist or can not be parsed
+fn build(dir: &Path, file: &Path) -> Gitignore {
+  if !file.is_file() {
+    return Gitignore::empty();
+  }
+  let mut builder = GitignoreBuilder::new(dir);
+  builder.add(file);
+  builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
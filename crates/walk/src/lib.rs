@@ -1,6 +1,7 @@
-use ::ignore::DirEntry;
-use doctor_core::Ignore;
+use ::ignore::{DirEntry, overrides::Override};
+use doctor_core::{ChangedFiles, Ignore};
 use extensions::Extensions;
+use filter::StandardFilters;
 use rayon::prelude::*;
 use std::{
   fs,
@@ -11,6 +12,7 @@ use typed_builder::TypedBuilder;
 
 mod error;
 mod extensions;
+mod filter;
 
 pub use error::WalkError;
 
@@ -20,6 +22,11 @@ pub struct WalkParallelJs {
 
   #[builder(default = Ignore::default())]
   pub ignore: Ignore,
+
+  /// Only visit these files instead of walking `cwd`, `ignore` and the gitignore and hidden
+  /// file filters of the walk still apply
+  #[builder(default = None)]
+  pub files: Option<ChangedFiles>,
 }
 
 impl WalkParallelJs {
@@ -30,91 +37,187 @@ impl WalkParallelJs {
     if file_type.is_dir() {
       return false;
     }
-    let Some(extension) = dir_entry.path().extension() else {
+    Self::has_wanted_extension(dir_entry.path(), extensions)
+  }
+
+  fn has_wanted_extension(path: &Path, extensions: &Extensions) -> bool {
+    let Some(extension) = path.extension() else {
       return false;
     };
     let extension = extension.to_string_lossy();
     extensions.0.contains(&extension.as_ref())
   }
 
+  /// `ignore` as negated overrides, a pattern that is not a valid glob is an error
+  fn overrides(&self) -> Result<Override, WalkError> {
+    let mut r#override = ignore::overrides::OverrideBuilder::new(&self.cwd);
+
+    for pattern in &self.ignore.0 {
+      r#override.add(format!("!{pattern}").as_str())?;
+    }
+
+    Ok(r#override.build()?)
+  }
+
+  /// The ignore rules of the walk, to ask about many paths without building them again
+  pub fn matcher(&self) -> Result<WalkMatcher, WalkError> {
+    Ok(WalkMatcher {
+      cwd: self.cwd.clone(),
+      overrides: self.overrides()?,
+      filters: StandardFilters::new(&self.cwd),
+    })
+  }
+
+  /// Whether walking `cwd` would visit `path`, see [`WalkMatcher::accepts`]
+  pub fn accepts(&self, path: &Path) -> Result<bool, WalkError> {
+    Ok(self.matcher()?.accepts(path))
+  }
+
+  /// The directories walking `cwd` would enter from `dir` on, see [`WalkMatcher::dirs`]
+  pub fn dirs(&self, dir: &Path) -> Result<Vec<PathBuf>, WalkError> {
+    Ok(self.matcher()?.dirs(dir))
+  }
+
   pub fn walk<F, R>(&self, f: F) -> Result<Vec<Result<R, WalkError>>, WalkError>
   where
     F: Fn(PathBuf) -> Result<R, WalkError> + Send + Sync,
     R: Send + Sync,
   {
-    let mut inner = ignore::WalkBuilder::new(&self.cwd);
+    let ext = Extensions::default();
+    let matcher = self.matcher()?;
 
-    let mut r#override = ignore::overrides::OverrideBuilder::new(&self.cwd);
+    let res = match &self.files {
+      Some(files) => files
+        .par_iter()
+        .filter(|path| Self::has_wanted_extension(path, &ext))
+        .filter(|path| !matcher.is_ignored(path))
+        .filter(|path| !matcher.filters.is_filtered(path))
+        .filter(|path| path.is_file())
+        .filter(|path| is_wanted_file(path))
+        .cloned()
+        .map(&f)
+        .collect::<Vec<Result<R, WalkError>>>(),
+      None => {
+        let mut inner = ignore::WalkBuilder::new(&self.cwd);
+        inner.overrides(matcher.overrides);
 
-    for pattern in &self.ignore.0 {
-      r#override.add(format!("!{pattern}").as_str()).unwrap();
-    }
+        inner
+          .build()
+          .par_bridge()
+          .filter_map(Result::ok)
+          .filter(|entry| self.is_wanted_entry(entry, &ext))
+          .map(|entry| entry.path().to_owned())
+          .filter(|path| path.is_file())
+          .filter(|path| is_wanted_file(path))
+          .map(&f)
+          .collect::<Vec<Result<R, WalkError>>>()
+      }
+    };
+
+    Ok(res)
+  }
+}
 
-    inner.overrides(r#override.build().unwrap());
+/// The ignore rules of a [`WalkParallelJs`], see [`WalkParallelJs::matcher`]
+///
+/// `ignore` and the gitignore files are read once, e.g. for every path of a batch of file
+/// events.
+#[derive(Debug, Clone)]
+pub struct WalkMatcher {
+  cwd: PathBuf,
+  overrides: Override,
+  filters: StandardFilters,
+}
 
-    let walker = inner.build();
+impl WalkMatcher {
+  /// Whether an explicitly given file is hit by `ignore`
+  fn is_ignored(&self, path: &Path) -> bool {
+    // 遍历时目录被忽略就不会进入，显式文件要把上层目录也匹配一遍
+    path
+      .ancestors()
+      .take_while(|ancestor| ancestor.starts_with(&self.cwd) && *ancestor != self.cwd)
+      .enumerate()
+      .any(|(depth, ancestor)| self.overrides.matched(ancestor, depth > 0).is_ignore())
+  }
 
-    let ext = Extensions::default();
-    let res = walker
-      .par_bridge()
+  /// Whether walking `cwd` would visit `path`, the file need not exist
+  pub fn accepts(&self, path: &Path) -> bool {
+    WalkParallelJs::has_wanted_extension(path, &Extensions::default())
+      && !self.is_ignored(path)
+      && !self.filters.is_filtered(path)
+  }
+
+  /// The directories walking `cwd` would enter from `dir` on, `dir` included
+  pub fn dirs(&self, dir: &Path) -> Vec<PathBuf> {
+    if dir != self.cwd && (self.is_ignored(dir) || self.filters.is_filtered(dir)) {
+      return vec![];
+    }
+
+    let mut inner = ignore::WalkBuilder::new(dir);
+    inner.overrides(self.overrides.clone());
+
+    inner
+      .build()
       .filter_map(Result::ok)
-      .filter(|entry| self.is_wanted_entry(entry, &ext))
-      .map(|entry| entry.path().to_owned())
-      .filter(|path| path.is_file())
-      .filter(|path| {
-        let Some(extension) = path.extension() else {
-          // 忽略
-          log::warn!("Ignore Unable to get file name extension: {:?}", path);
-          return false;
-        };
-
-        // if extension == "js" {
-        //   if is_minified_by_characteristics(path) {
-        //     log::warn!("Ignore minified js file: {:?}", path);
-        //     return false;
-        //   }
-        // }
-
-        // TODO https://github.com/oxc-project/oxc-miette/pull/20/files 临时忽略
-        if let Ok(content) = fs::read_to_string(path) {
-          if content
-            .lines()
-            // .any(|line| line.len() as usize > u16::MAX as usize)
-            .any(|line| line.len() as usize > 2000)
-          {
-            log::warn!("Ignore large line length file: {:?}", path);
-            return false;
-          }
-        }
-
-        if extension == "ts" {
-          if is_ts_video(path) {
-            log::warn!("Ignore ts video file: {:?}", path);
-            return false;
-          }
-        }
-
-        // 大于 1mb 的过滤
-        if let Ok(metadata) = std::fs::metadata(path) {
-          // MB 单位
-          let size = metadata.len() / 1024 / 1024;
-          let is_large_file = size > 1;
-          if is_large_file {
-            log::warn!(
-              "Ignore large file, Only support 1MB: {:?} ({}MB)",
-              path,
-              size
-            );
-          }
-          return !is_large_file;
-        } else {
-          return false;
-        }
+      .filter(|entry| {
+        entry
+          .file_type()
+          .is_some_and(|file_type| file_type.is_dir())
       })
-      .map(f)
-      .collect::<Vec<Result<R, WalkError>>>();
+      .map(DirEntry::into_path)
+      .collect()
+  }
+}
 
-    Ok(res)
+/// Skip files oxc can not handle, e.g. ts videos, very long lines and large files
+fn is_wanted_file(path: &Path) -> bool {
+  let Some(extension) = path.extension() else {
+    // 忽略
+    log::warn!("Ignore Unable to get file name extension: {:?}", path);
+    return false;
+  };
+
+  // if extension == "js" {
+  //   if is_minified_by_characteristics(path) {
+  //     log::warn!("Ignore minified js file: {:?}", path);
+  //     return false;
+  //   }
+  // }
+
+  // TODO https://github.com/oxc-project/oxc-miette/pull/20/files 临时忽略
+  if let Ok(content) = fs::read_to_string(path) {
+    if content
+      .lines()
+      // .any(|line| line.len() as usize > u16::MAX as usize)
+      .any(|line| line.len() as usize > 2000)
+    {
+      log::warn!("Ignore large line length file: {:?}", path);
+      return false;
+    }
+  }
+
+  if extension == "ts" {
+    if is_ts_video(path) {
+      log::warn!("Ignore ts video file: {:?}", path);
+      return false;
+    }
+  }
+
+  // 大于 1mb 的过滤
+  if let Ok(metadata) = std::fs::metadata(path) {
+    // MB 单位
+    let size = metadata.len() / 1024 / 1024;
+    let is_large_file = size > 1;
+    if is_large_file {
+      log::warn!(
+        "Ignore large file, Only support 1MB: {:?} ({}MB)",
+        path,
+        size
+      );
+    }
+    return !is_large_file;
+  } else {
+    return false;
   }
 }
 
@@ -182,4 +285,96 @@ mod tests {
     let res = walk_parallel_js.walk(|path| Ok(path)).unwrap();
     assert!(res.len() == 1);
   }
+
+  #[test]
+  fn should_only_visit_given_files_not_ignored() {
+    let cwd = PathBuf::from("./fixtures");
+    let walk_parallel_js = WalkParallelJs::builder()
+      .cwd(cwd.clone())
+      .files(Some(ChangedFiles::new(
+        &cwd,
+        ["a.js", "a.min.js", "missing.js", "README.md"],
+      )))
+      .build();
+    let res = walk_parallel_js.walk(|path| Ok(path)).unwrap();
+    let res = res.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+    assert_eq!(res, vec![cwd.join("a.js")]);
+  }
+
+  #[test]
+  fn should_filter_given_files_like_the_walk() {
+    let cwd = std::env::temp_dir().join(format!("doctor-walk-filters-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&cwd);
+    for dir in [".git", "dist", ".hidden", "src/generated"] {
+      fs::create_dir_all(cwd.join(dir)).unwrap();
+    }
+    fs::write(cwd.join(".gitignore"), "dist/\n").unwrap();
+    fs::write(cwd.join("src/.gitignore"), "generated\n").unwrap();
+    let files = [
+      "dist/a.js",
+      ".hidden/b.js",
+      "src/generated/c.js",
+      "src/d.js",
+    ];
+    for file in files {
+      fs::write(cwd.join(file), "").unwrap();
+    }
+
+    let walk_parallel_js = WalkParallelJs::builder()
+      .cwd(cwd.clone())
+      .files(Some(ChangedFiles::new(&cwd, files)))
+      .build();
+    let res = walk_parallel_js.walk(|path| Ok(path)).unwrap();
+    let res = res.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+    assert_eq!(res, vec![cwd.join("src/d.js")]);
+
+    let walked = WalkParallelJs::builder().cwd(cwd.clone()).build();
+    let res = walked.walk(|path| Ok(path)).unwrap();
+    let res = res.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+    assert_eq!(res, vec![cwd.join("src/d.js")]);
+
+    assert!(walked.accepts(&cwd.join("src/e.ts")).unwrap());
+    assert!(!walked.accepts(&cwd.join("dist/e.ts")).unwrap());
+    assert!(!walked.accepts(&cwd.join(".hidden/e.ts")).unwrap());
+
+    let mut dirs = walked.dirs(&cwd).unwrap();
+    dirs.sort();
+    assert_eq!(dirs, vec![cwd.clone(), cwd.join("src")]);
+    assert!(walked.dirs(&cwd.join("dist")).unwrap().is_empty());
+
+    fs::remove_dir_all(&cwd).unwrap();
+  }
+
+  #[test]
+  fn should_accept_wanted_paths_only() {
+    let cwd = PathBuf::from("/repo");
+    let walk_parallel_js = WalkParallelJs::builder().cwd(cwd.clone()).build();
+    assert!(
+      walk_parallel_js
+        .accepts(&cwd.join("src/deleted.ts"))
+        .unwrap()
+    );
+    assert!(
+      !walk_parallel_js
+        .accepts(&cwd.join("node_modules/a/index.js"))
+        .unwrap()
+    );
+    assert!(!walk_parallel_js.accepts(&cwd.join("src/a.css")).unwrap());
+  }
+
+  #[test]
+  fn should_report_invalid_ignore_pattern() {
+    let cwd = PathBuf::from("./fixtures");
+    let walk_parallel_js = WalkParallelJs::builder()
+      .cwd(cwd.clone())
+      .ignore(Ignore(vec!["src/{a".to_string()]))
+      .build();
+
+    assert!(matches!(
+      walk_parallel_js.walk(|path| Ok(path)),
+      Err(WalkError::IgnoreError(_))
+    ));
+    assert!(walk_parallel_js.accepts(&cwd.join("a.js")).is_err());
+    assert!(walk_parallel_js.dirs(&cwd).is_err());
+  }
 }
@@ -1,6 +1,6 @@
 
 import { cac } from "cac";
-import { initializeLogger, JsSpecifications } from "@shined/doctor-binding"
+import { initializeLogger, JsSpecifications, sfconfigSchema } from "@shined/doctor-binding"
 import { performance } from "node:perf_hooks"
 const cli = cac("doctor");
 
@@ -59,6 +59,19 @@ cli.command('fix','fix lint')
     console.log(`Time taken: ${end - start} milliseconds`);
   });
 
+cli.command('print-config','print .sfconfig/spec.json with extends resolved')
+  .option('--cwd <path>', 'Current working directory')
+  .action(async (options) => {
+    const cwd = options.cwd || process.cwd();
+    const standards = await JsSpecifications.create(cwd);
+    console.log(standards.printConfig());
+  });
+
+cli.command('schema','print the JSON Schema of .sfconfig/spec.json')
+  .action(() => {
+    console.log(sfconfigSchema());
+  });
+
 cli.help();
 
 cli.parse();